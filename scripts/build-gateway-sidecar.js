@@ -0,0 +1,29 @@
+// Builds the mcp-runner-gateway helper and copies it to src-tauri/binaries under the
+// target-triple name Tauri expects for `bundle.externalBin`. Run before `tauri build`
+// (see the `tauri:build` script); installed bundles place it next to the app binary.
+import { execFileSync } from "node:child_process";
+import { copyFileSync, mkdirSync } from "node:fs";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const root = join(dirname(fileURLToPath(import.meta.url)), "..");
+const tauriDir = join(root, "src-tauri");
+
+const rustInfo = execFileSync("rustc", ["-vV"], { encoding: "utf8" });
+const triple = process.env.TAURI_ENV_TARGET_TRIPLE ?? /^host: (\S+)$/m.exec(rustInfo)?.[1];
+if (!triple) {
+  throw new Error("Could not determine the target triple from `rustc -vV`");
+}
+
+execFileSync(
+  "cargo",
+  ["build", "--release", "--bin", "mcp-runner-gateway", "--target", triple],
+  { cwd: tauriDir, stdio: "inherit" },
+);
+
+const extension = triple.includes("windows") ? ".exe" : "";
+const built = join(tauriDir, "target", triple, "release", `mcp-runner-gateway${extension}`);
+const binaries = join(tauriDir, "binaries");
+mkdirSync(binaries, { recursive: true });
+copyFileSync(built, join(binaries, `mcp-runner-gateway-${triple}${extension}`));
+console.log(`Copied mcp-runner-gateway for ${triple} to src-tauri/binaries`);
@@ -0,0 +1,126 @@
+// Stdio front end for the runner's gateway, for clients that only launch MCP servers as
+// commands. Reads JSON-RPC messages line by line from stdin, posts each to the gateway's
+// Streamable HTTP endpoint and writes the replies to stdout.
+//
+//   mcp-runner-gateway [--url http://127.0.0.1:7420/mcp]
+//
+// The URL can also come from MCP_RUNNER_GATEWAY_URL. The runner app has to be open with the
+// gateway started.
+use std::io::{self, BufRead, Write};
+use std::thread;
+
+use serde_json::{json, Value};
+
+const DEFAULT_URL: &str = "http://127.0.0.1:7420/mcp";
+
+fn gateway_url() -> String {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--url" {
+            if let Some(url) = args.next() {
+                return url;
+            }
+        } else if let Some(url) = arg.strip_prefix("--url=") {
+            return url.to_string();
+        }
+    }
+    std::env::var("MCP_RUNNER_GATEWAY_URL").unwrap_or_else(|_| DEFAULT_URL.to_string())
+}
+
+// The error response a request gets when the gateway couldn't answer it; notifications get none
+fn error_reply(line: &str, message: String) -> Option<String> {
+    let request: Value = serde_json::from_str(line).ok()?;
+    request.get("method")?;
+    let id = request.get("id")?;
+    Some(json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32603, "message": message } }).to_string())
+}
+
+// JSON-RPC messages in a gateway response. Anything that isn't one (an HTTP error page, a
+// plain-text 404) must not reach stdout, where the client expects nothing but JSON-RPC.
+fn replies(url: &str, line: &str, response: reqwest::blocking::Response) -> Result<Vec<String>, String> {
+    let status = response.status();
+    let event_stream = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"));
+    let body = response.text().map_err(|e| format!("Failed to read gateway response: {}", e))?;
+    if !status.is_success() {
+        return Err(format!("Gateway at {} answered HTTP {}: {}", url, status, body.trim()));
+    }
+
+    let payloads: Vec<&str> = if event_stream {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim)
+            .collect()
+    } else {
+        vec![body.trim()]
+    };
+    let mut replies = Vec::new();
+    for payload in payloads.into_iter().filter(|payload| !payload.is_empty()) {
+        match serde_json::from_str::<Value>(payload) {
+            Ok(Value::Array(batch)) => replies.extend(batch.iter().map(Value::to_string)),
+            Ok(message) => replies.push(message.to_string()),
+            Err(_) => return Err(format!("Gateway at {} sent a non-JSON reply to {}", url, line)),
+        }
+    }
+    Ok(replies)
+}
+
+fn forward(client: &reqwest::blocking::Client, url: &str, line: &str) {
+    let result = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json, text/event-stream")
+        .body(line.to_string())
+        .send()
+        .map_err(|e| format!("Gateway unreachable at {}: {}", url, e))
+        .and_then(|response| replies(url, line, response));
+    let replies = match result {
+        Ok(replies) => replies,
+        Err(e) => {
+            eprintln!("mcp-runner-gateway: {}", e);
+            // Requests still need an answer or the client will wait forever
+            error_reply(line, e).into_iter().collect()
+        }
+    };
+
+    // One lock per batch keeps each reply on its own line when threads finish together
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for reply in replies {
+        if writeln!(stdout, "{}", reply).is_err() {
+            return;
+        }
+    }
+    let _ = stdout.flush();
+}
+
+fn main() {
+    let url = gateway_url();
+    // No overall timeout: tool calls are bounded by the gateway itself
+    let client = match reqwest::blocking::Client::builder().timeout(None).build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("mcp-runner-gateway: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Each message on its own thread, so a long tool call doesn't hold up the others
+    let mut in_flight: Vec<thread::JoinHandle<()>> = Vec::new();
+    for line in io::stdin().lock().lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        in_flight.retain(|handle| !handle.is_finished());
+        let client = client.clone();
+        let url = url.clone();
+        in_flight.push(thread::spawn(move || forward(&client, &url, &line)));
+    }
+    // Stdin closed; let pending requests deliver their replies before exiting
+    for handle in in_flight {
+        let _ = handle.join();
+    }
+}
@@ -0,0 +1,370 @@
+// Exposes a stdio server on its configured `port` for clients that only speak remote
+// transports: MCP Streamable HTTP at /mcp and the older HTTP+SSE transport at /sse with
+// /messages. The runner keeps owning the child; every client shares its one stdio channel,
+// with request ids kept apart by the channel and the handshake done once on their behalf.
+//
+// Requests the server sends to clients (sampling, roots, elicitation) aren't routed to a
+// bridge client; the channel answers them itself as it does without a bridge.
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+use tiny_http::{Method, Request, Server};
+
+use crate::http::{self, HttpResponse, INTERNAL_ERROR};
+use crate::mcp;
+use crate::mcp_channel;
+use crate::tool_call::TOOL_CALL_TIMEOUT;
+
+const STREAMABLE_PATH: &str = "/mcp";
+const SSE_PATH: &str = "/sse";
+const SSE_MESSAGES_PATH: &str = "/messages";
+
+// Comment lines sent on idle streams so clients that went away are noticed
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct BridgeStatus {
+    id: String,
+    port: u16,
+    pub(crate) url: String,
+    pub(crate) sse_url: String,
+    clients: usize,
+}
+
+// A connected client; `events` is set while it holds an SSE stream open
+struct Client {
+    events: Option<mpsc::Sender<String>>,
+}
+
+struct Bridge {
+    server: Arc<Server>,
+    port: u16,
+    clients: Mutex<HashMap<String, Client>>,
+    next_client: AtomicU64,
+}
+
+// Store for the bridge listening in front of each server that has one
+pub(crate) struct BridgeStore {
+    inner: Mutex<HashMap<String, Arc<Bridge>>>,
+}
+
+impl BridgeStore {
+    pub(crate) fn new() -> Self {
+        BridgeStore {
+            inner: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Bridge {
+    fn status(&self, id: &str) -> BridgeStatus {
+        BridgeStatus {
+            id: id.to_string(),
+            port: self.port,
+            url: format!("http://127.0.0.1:{}{}", self.port, STREAMABLE_PATH),
+            sse_url: format!("http://127.0.0.1:{}{}", self.port, SSE_PATH),
+            clients: self.clients.lock().map(|clients| clients.len()).unwrap_or(0),
+        }
+    }
+
+    // Session ids only need to be unguessable enough that another local page can't pick one up
+    fn add_client(&self, events: Option<mpsc::Sender<String>>) -> String {
+        let count = self.next_client.fetch_add(1, Ordering::SeqCst);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or(0);
+        let session_id = format!("{:x}{:x}{:04x}", nanos, std::process::id(), count);
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.insert(session_id.clone(), Client { events });
+        }
+        session_id
+    }
+
+    fn remove_client(&self, session_id: &str) -> bool {
+        self.clients.lock().is_ok_and(|mut clients| clients.remove(session_id).is_some())
+    }
+
+    fn has_client(&self, session_id: &str) -> bool {
+        self.clients.lock().is_ok_and(|clients| clients.contains_key(session_id))
+    }
+
+    fn send_to(&self, session_id: &str, message: &Value) -> bool {
+        let Ok(clients) = self.clients.lock() else {
+            return false;
+        };
+        clients
+            .get(session_id)
+            .and_then(|client| client.events.as_ref())
+            .is_some_and(|events| events.send(message.to_string()).is_ok())
+    }
+}
+
+pub(crate) fn list<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<BridgeStatus>, String> {
+    let store = app.state::<BridgeStore>();
+    let bridges = store.inner.lock().map_err(|e| e.to_string())?;
+    let mut statuses: Vec<BridgeStatus> = bridges.iter().map(|(id, bridge)| bridge.status(id)).collect();
+    statuses.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(statuses)
+}
+
+// Starts the bridge for `id` on `port`, replacing one already running on another port
+pub(crate) fn start<R: Runtime>(app: &AppHandle<R>, id: &str, port: u16) -> Result<BridgeStatus, String> {
+    let store = app.state::<BridgeStore>();
+    let mut bridges = store.inner.lock().map_err(|e| e.to_string())?;
+    if let Some(bridge) = bridges.get(id) {
+        if bridge.port == port {
+            return Ok(bridge.status(id));
+        }
+        shut_down(bridge);
+        bridges.remove(id);
+    }
+
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to start bridge on port {}: {}", port, e))?;
+    let bridge = Arc::new(Bridge {
+        server: Arc::new(server),
+        port,
+        clients: Mutex::new(HashMap::new()),
+        next_client: AtomicU64::new(1),
+    });
+    bridges.insert(id.to_string(), Arc::clone(&bridge));
+
+    let app = app.clone();
+    let id = id.to_string();
+    let status = bridge.status(&id);
+    thread::spawn(move || {
+        let server = Arc::clone(&bridge.server);
+        for request in server.incoming_requests() {
+            let app = app.clone();
+            let id = id.clone();
+            let bridge = Arc::clone(&bridge);
+            thread::spawn(move || handle_http(&app, &id, &bridge, request));
+        }
+        println!("Bridge: Listener for {} on port {} stopped", id, port);
+    });
+    Ok(status)
+}
+
+pub(crate) fn stop<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    let store = app.state::<BridgeStore>();
+    let Ok(mut bridges) = store.inner.lock() else {
+        return;
+    };
+    if let Some(bridge) = bridges.remove(id) {
+        shut_down(&bridge);
+    }
+}
+
+pub(crate) fn stop_all<R: Runtime>(app: &AppHandle<R>) {
+    let store = app.state::<BridgeStore>();
+    let Ok(mut bridges) = store.inner.lock() else {
+        return;
+    };
+    for (_, bridge) in bridges.drain() {
+        shut_down(&bridge);
+    }
+}
+
+fn shut_down(bridge: &Bridge) {
+    // Dropping the senders ends every open stream
+    if let Ok(mut clients) = bridge.clients.lock() {
+        clients.clear();
+    }
+    bridge.server.unblock();
+}
+
+// Passes a notification from the server on to every client with an open stream
+pub(crate) fn broadcast<R: Runtime>(app: &AppHandle<R>, id: &str, message: &Value) {
+    let bridge = match app.state::<BridgeStore>().inner.lock() {
+        Ok(bridges) => bridges.get(id).cloned(),
+        Err(_) => None,
+    };
+    let Some(bridge) = bridge else {
+        return;
+    };
+    let Ok(mut clients) = bridge.clients.lock() else {
+        return;
+    };
+    let text = message.to_string();
+    for client in clients.values_mut() {
+        if let Some(events) = &client.events {
+            if events.send(text.clone()).is_err() {
+                client.events = None;
+            }
+        }
+    }
+}
+
+fn handle_http<R: Runtime>(app: &AppHandle<R>, id: &str, bridge: &Bridge, mut request: Request) {
+    if !http::origin_allowed(&request) {
+        respond(request, http::text_response(403, "Forbidden origin"));
+        return;
+    }
+
+    let path = http::path(&request).to_string();
+    let method = request.method().clone();
+    match (path.as_str(), method) {
+        (STREAMABLE_PATH, Method::Post) => {
+            let response = streamable_post(app, id, bridge, &mut request);
+            respond(request, response);
+        }
+        (STREAMABLE_PATH, Method::Get) => {
+            let session_id = http::header_value(&request, "Mcp-Session-Id").map(str::to_string);
+            let (sender, receiver) = mpsc::channel();
+            match session_id {
+                Some(session_id) => {
+                    let attached = bridge.clients.lock().is_ok_and(|mut clients| match clients.get_mut(&session_id) {
+                        Some(client) => {
+                            client.events = Some(sender);
+                            true
+                        }
+                        None => false,
+                    });
+                    if !attached {
+                        respond(request, http::text_response(404, "Unknown session"));
+                        return;
+                    }
+                }
+                None => {
+                    // A stream without a session lasts as long as the connection
+                    let session_id = bridge.add_client(Some(sender));
+                    stream_events(request, receiver, None);
+                    bridge.remove_client(&session_id);
+                    return;
+                }
+            }
+            stream_events(request, receiver, None);
+        }
+        (STREAMABLE_PATH, Method::Delete) => {
+            let removed = http::header_value(&request, "Mcp-Session-Id")
+                .is_some_and(|session_id| bridge.remove_client(session_id));
+            respond(request, http::text_response(if removed { 200 } else { 404 }, ""));
+        }
+        (SSE_PATH, Method::Get) => {
+            let (sender, receiver) = mpsc::channel();
+            let session_id = bridge.add_client(Some(sender));
+            let endpoint = format!("{}?sessionId={}", SSE_MESSAGES_PATH, session_id);
+            stream_events(request, receiver, Some(endpoint));
+            bridge.remove_client(&session_id);
+        }
+        (SSE_MESSAGES_PATH, Method::Post) => {
+            let response = sse_post(app, id, bridge, &mut request);
+            respond(request, response);
+        }
+        (STREAMABLE_PATH | SSE_PATH | SSE_MESSAGES_PATH, _) => {
+            respond(request, http::text_response(405, "Method not allowed"));
+        }
+        _ => respond(request, http::text_response(404, "Not found")),
+    }
+}
+
+fn respond(request: Request, response: HttpResponse) {
+    if let Err(e) = request.respond(response) {
+        eprintln!("Bridge: Failed to send response: {}", e);
+    }
+}
+
+// Streamable HTTP: replies come back in the POST response; initialize starts a session
+fn streamable_post<R: Runtime>(app: &AppHandle<R>, id: &str, bridge: &Bridge, request: &mut Request) -> HttpResponse {
+    let session_id = http::header_value(request, "Mcp-Session-Id").map(str::to_string);
+    if session_id.as_deref().is_some_and(|session_id| !bridge.has_client(session_id)) {
+        return http::text_response(404, "Unknown session");
+    }
+    let message = match http::read_json(request) {
+        Ok(message) => message,
+        Err(response) => return response,
+    };
+
+    let is_initialize = message.get("method").and_then(Value::as_str) == Some("initialize");
+    match http::handle_batch(message, |message| handle_message(app, id, message)) {
+        Some(reply) if is_initialize => {
+            let session_id = bridge.add_client(None);
+            http::json_response(200, &reply).with_header(http::header("Mcp-Session-Id", &session_id))
+        }
+        Some(reply) => http::json_response(200, &reply),
+        None => http::accepted(),
+    }
+}
+
+// Legacy HTTP+SSE: the POST is only acknowledged; replies go out on the client's stream
+fn sse_post<R: Runtime>(app: &AppHandle<R>, id: &str, bridge: &Bridge, request: &mut Request) -> HttpResponse {
+    let Some(session_id) = http::query_param(request, "sessionId").map(str::to_string) else {
+        return http::text_response(400, "Missing sessionId");
+    };
+    if !bridge.has_client(&session_id) {
+        return http::text_response(404, "Unknown session");
+    }
+    let message = match http::read_json(request) {
+        Ok(message) => message,
+        Err(response) => return response,
+    };
+    if let Some(reply) = http::handle_batch(message, |message| handle_message(app, id, message)) {
+        if !bridge.send_to(&session_id, &reply) {
+            return http::text_response(410, "Stream closed");
+        }
+    }
+    http::accepted()
+}
+
+// Forwards one client message to the server; None when no reply is due
+fn handle_message<R: Runtime>(app: &AppHandle<R>, id: &str, message: Value) -> Option<Value> {
+    // Responses to server requests have nowhere to go (see the top of the file)
+    let method = message.get("method").and_then(Value::as_str)?.to_string();
+    let params = message.get("params").cloned().unwrap_or_else(|| serde_json::json!({}));
+    let request_id = message.get("id").cloned();
+
+    let (channel, session) = match mcp_channel(app, id, mcp::REQUEST_TIMEOUT) {
+        Ok(connected) => connected,
+        Err(e) => return request_id.map(|request_id| http::error_response(request_id, INTERNAL_ERROR, &e)),
+    };
+
+    let Some(request_id) = request_id else {
+        // The runner already sent `initialized`, and cancellations name the client's request
+        // ids, which the server never saw
+        if method != "notifications/initialized" && method != "notifications/cancelled" {
+            if let Err(e) = channel.notify(&method, params) {
+                eprintln!("Bridge: Failed to forward '{}' to {}: {}", method, id, e);
+            }
+        }
+        return None;
+    };
+
+    if method == "initialize" {
+        return Some(serde_json::json!({ "jsonrpc": "2.0", "id": request_id, "result": session.initialize_result() }));
+    }
+    match channel.call(&method, params, TOOL_CALL_TIMEOUT) {
+        Ok(mut response) => {
+            response["id"] = request_id;
+            Some(response)
+        }
+        Err(e) => Some(http::error_response(request_id, INTERNAL_ERROR, &e)),
+    }
+}
+
+// Holds the connection open as an event stream until the client goes away or the bridge stops.
+// Written straight to the socket: tiny_http would buffer a streamed body.
+fn stream_events(request: Request, events: mpsc::Receiver<String>, endpoint: Option<String>) {
+    let mut writer = request.into_writer();
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+    let mut result = writer.write_all(head.as_bytes());
+    if let Some(endpoint) = endpoint {
+        result = result.and_then(|_| write!(writer, "event: endpoint\ndata: {}\n\n", endpoint));
+    }
+    result = result.and_then(|_| writer.flush());
+
+    while result.is_ok() {
+        result = match events.recv_timeout(KEEPALIVE_INTERVAL) {
+            Ok(message) => write!(writer, "event: message\ndata: {}\n\n", message),
+            Err(mpsc::RecvTimeoutError::Timeout) => writer.write_all(b": keepalive\n\n"),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        .and_then(|_| writer.flush());
+    }
+}
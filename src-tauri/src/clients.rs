@@ -0,0 +1,626 @@
+// Server definitions kept by other MCP clients (Claude Desktop, Cursor, VS Code, Windsurf):
+// where their config files live, how their entries map onto ours and back
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::remote::Transport;
+use crate::output::now_ms;
+use crate::{config_file, Config, MCPServerConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) enum Client {
+    #[serde(rename = "claude-desktop")]
+    ClaudeDesktop,
+    #[serde(rename = "cursor")]
+    Cursor,
+    #[serde(rename = "vscode")]
+    VsCode,
+    #[serde(rename = "windsurf")]
+    Windsurf,
+}
+
+pub(crate) const CLIENTS: &[Client] = &[Client::ClaudeDesktop, Client::Cursor, Client::VsCode, Client::Windsurf];
+
+// How an imported entry relates to a server of the same name already in the config
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Conflict {
+    // No server by that name yet
+    None,
+    // Same name and the same definition; importing changes nothing
+    Identical,
+    // Same name, different definition
+    Differs,
+}
+
+// What to do with a chosen entry whose name is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OnConflict {
+    #[default]
+    Skip,
+    Overwrite,
+    // Import under the first free `<name>-<client>` / `<name>-<client>-<n>`
+    Rename,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub(crate) struct ImportCandidate {
+    name: String,
+    config: MCPServerConfig,
+    conflict: Conflict,
+    // Things in the original entry the runner can't carry over as-is
+    warnings: Vec<String>,
+}
+
+// One client config file and the servers found in it
+#[derive(Clone, serde::Serialize)]
+pub(crate) struct ImportSource {
+    client: Client,
+    path: String,
+    exists: bool,
+    servers: Vec<ImportCandidate>,
+    // Set when the file exists but couldn't be read or parsed
+    error: Option<String>,
+}
+
+#[derive(Clone, Default, serde::Serialize)]
+pub(crate) struct ImportOutcome {
+    // Names as they ended up in our config
+    imported: Vec<String>,
+    skipped: Vec<String>,
+    // The config after the merge
+    pub(crate) config: Config,
+}
+
+impl Client {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Client::ClaudeDesktop => "claude-desktop",
+            Client::Cursor => "cursor",
+            Client::VsCode => "vscode",
+            Client::Windsurf => "windsurf",
+        }
+    }
+
+    // The global config file the client reads its servers from
+    pub(crate) fn config_path<R: Runtime>(self, app: &AppHandle<R>) -> Result<PathBuf, String> {
+        let path = app.path();
+        let home = || path.home_dir().map_err(|_| "Failed to get home directory".to_string());
+        let config = || path.config_dir().map_err(|_| "Failed to get config directory".to_string());
+        Ok(match self {
+            Client::ClaudeDesktop => config()?.join("Claude").join("claude_desktop_config.json"),
+            Client::Cursor => home()?.join(".cursor").join("mcp.json"),
+            Client::VsCode => config()?.join("Code").join("User").join("mcp.json"),
+            Client::Windsurf => home()?.join(".codeium").join("windsurf").join("mcp_config.json"),
+        })
+    }
+
+    // The key holding the server map; VS Code calls it `servers`
+    pub(crate) fn servers_key(self) -> &'static str {
+        match self {
+            Client::VsCode => "servers",
+            _ => "mcpServers",
+        }
+    }
+}
+
+// Reads the servers of every known client (or just `client`, optionally from `path` for
+// project-level files like .cursor/mcp.json), marking conflicts with `existing`
+pub(crate) fn preview<R: Runtime>(
+    app: &AppHandle<R>,
+    client: Option<Client>,
+    path: Option<&str>,
+    existing: &Config,
+) -> Result<Vec<ImportSource>, String> {
+    let clients = match client {
+        Some(client) => vec![client],
+        None if path.is_some() => return Err("A client is required to import from a specific file".to_string()),
+        None => CLIENTS.to_vec(),
+    };
+
+    let mut sources = Vec::new();
+    for client in clients {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => client.config_path(app)?,
+        };
+        sources.push(read_source(client, &path, existing));
+    }
+    Ok(sources)
+}
+
+fn read_source(client: Client, path: &Path, existing: &Config) -> ImportSource {
+    let mut source = ImportSource {
+        client,
+        path: path.display().to_string(),
+        exists: path.exists(),
+        servers: Vec::new(),
+        error: None,
+    };
+    if !source.exists {
+        return source;
+    }
+
+    match read_servers(client, path) {
+        Ok(servers) => {
+            source.servers = servers
+                .into_iter()
+                .map(|(name, config, warnings)| ImportCandidate {
+                    conflict: conflict(existing.mcp_servers.get(&name), &config),
+                    name,
+                    config,
+                    warnings,
+                })
+                .collect();
+        }
+        Err(e) => source.error = Some(e),
+    }
+    source
+}
+
+// Parses `path` and converts each entry, sorted by name
+pub(crate) fn read_servers(client: Client, path: &Path) -> Result<Vec<(String, MCPServerConfig, Vec<String>)>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let document = parse_jsonc(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    let Some(entries) = server_map(client, &document) else {
+        return Ok(Vec::new());
+    };
+    let mut servers: Vec<_> = entries
+        .iter()
+        .map(|(name, entry)| {
+            let (config, warnings) = convert(entry);
+            (name.clone(), config, warnings)
+        })
+        .collect();
+    servers.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(servers)
+}
+
+// The client's own key first, then the other spellings (VS Code settings.json nests `mcp.servers`)
+fn server_map(client: Client, document: &Value) -> Option<&Map<String, Value>> {
+    [client.servers_key(), "mcpServers", "servers"]
+        .iter()
+        .find_map(|key| document.get(key))
+        .or_else(|| document.get("mcp").and_then(|mcp| mcp.get("servers")))
+        .and_then(Value::as_object)
+}
+
+// Where `sync` puts servers: whichever key the file already uses, the client's own otherwise
+fn servers_keys(client: Client, document: &Value) -> Vec<&'static str> {
+    let existing = [client.servers_key(), "mcpServers", "servers"]
+        .into_iter()
+        .find(|key| document.get(key).is_some());
+    match existing {
+        Some(key) => vec![key],
+        None if document.get("mcp").and_then(|mcp| mcp.get("servers")).is_some() => vec!["mcp", "servers"],
+        None => vec![client.servers_key()],
+    }
+}
+
+// Maps a client's entry onto ours. Unknown fields are dropped with a warning rather than failing
+// the whole import.
+fn convert(entry: &Value) -> (MCPServerConfig, Vec<String>) {
+    let mut config = MCPServerConfig::default();
+    let mut warnings = Vec::new();
+    let Some(entry) = entry.as_object() else {
+        warnings.push("Entry is not an object".to_string());
+        return (config, warnings);
+    };
+
+    for (key, value) in entry {
+        match key.as_str() {
+            "command" => config.command = string(value),
+            "args" => config.args = strings(value),
+            "env" => config.env = string_map(value),
+            "cwd" => config.cwd = value.as_str().map(str::to_string),
+            // Windsurf spells it serverUrl
+            "url" | "serverUrl" => config.url = value.as_str().map(str::to_string),
+            "headers" => config.headers = string_map(value),
+            "type" | "transport" => match value.as_str().unwrap_or_default() {
+                "stdio" => config.transport = Some(Transport::Stdio),
+                "sse" => config.transport = Some(Transport::Sse),
+                "http" | "streamable-http" | "streamableHttp" => config.transport = Some(Transport::StreamableHttp),
+                other => warnings.push(format!("Unknown transport '{}'", other)),
+            },
+            // Editor-only settings with no meaning for the runner
+            "disabled" | "autoApprove" | "alwaysAllow" | "description" | "gallery" | "version" => {}
+            "envFile" => warnings.push("'envFile' is not supported; copy the variables into 'env'".to_string()),
+            other => warnings.push(format!("Ignored field '{}'", other)),
+        }
+    }
+
+    let uses_inputs = std::iter::once(&config.command)
+        .chain(&config.args)
+        .chain(config.env.values())
+        .chain(config.headers.values())
+        .any(|value| value.contains("${input:"));
+    if uses_inputs {
+        warnings.push("Uses ${input:...} variables, which are not resolved; replace them with values".to_string());
+    }
+    if let Err(e) = config.validate() {
+        warnings.push(e);
+    }
+    (config, warnings)
+}
+
+fn string(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+fn string_map(value: &Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn conflict(existing: Option<&MCPServerConfig>, incoming: &MCPServerConfig) -> Conflict {
+    match existing {
+        None => Conflict::None,
+        Some(existing) if serde_json::to_value(existing).ok() == serde_json::to_value(incoming).ok() => {
+            Conflict::Identical
+        }
+        Some(_) => Conflict::Differs,
+    }
+}
+
+// Adds the entries named in `names` from `client`'s file to `config`
+pub(crate) fn merge<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &mut Config,
+    client: Client,
+    path: Option<&str>,
+    names: &[String],
+    on_conflict: OnConflict,
+) -> Result<ImportOutcome, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => client.config_path(app)?,
+    };
+    let mut servers: HashMap<String, MCPServerConfig> = read_servers(client, &path)?
+        .into_iter()
+        .map(|(name, config, _)| (name, config))
+        .collect();
+
+    let mut outcome = ImportOutcome::default();
+    for name in names {
+        let server = servers
+            .remove(name)
+            .ok_or_else(|| format!("Server '{}' not found in {}", name, path.display()))?;
+        let target = match (conflict(config.mcp_servers.get(name), &server), on_conflict) {
+            (Conflict::None, _) | (Conflict::Differs, OnConflict::Overwrite) => name.clone(),
+            (Conflict::Differs, OnConflict::Rename) => free_name(config, name, client),
+            _ => {
+                outcome.skipped.push(name.clone());
+                continue;
+            }
+        };
+        config.mcp_servers.insert(target.clone(), server);
+        outcome.imported.push(target);
+    }
+    outcome.config = config.clone();
+    Ok(outcome)
+}
+
+fn free_name(config: &Config, name: &str, client: Client) -> String {
+    let base = format!("{}-{}", name, client.label());
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while config.mcp_servers.contains_key(&candidate) {
+        candidate = format!("{}-{}", base, n);
+        n += 1;
+    }
+    candidate
+}
+
+// Name of the single entry that points a client at the gateway
+pub(crate) const GATEWAY_ENTRY: &str = "mcp-server-runner";
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Change {
+    Added,
+    Updated,
+    Unchanged,
+}
+
+// One entry of the client's server map, before and after the sync
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct EntryChange {
+    name: String,
+    change: Change,
+    before: Option<Value>,
+    after: Value,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct SyncReport {
+    client: Client,
+    path: String,
+    // Copy of the file as it was before writing, if it existed and anything changed
+    backup: Option<String>,
+    // False for dry runs and when every entry was already up to date
+    pub(crate) written: bool,
+    changes: Vec<EntryChange>,
+}
+
+// Our server as an entry in the client's format
+pub(crate) fn export_entry(client: Client, server: &MCPServerConfig) -> Result<Value, String> {
+    server.validate()?;
+    let mut entry = Map::new();
+    match server.transport() {
+        Transport::Stdio => {
+            if client == Client::VsCode {
+                entry.insert("type".to_string(), json!("stdio"));
+            }
+            if server.shell {
+                let (shell, shell_arg) = if cfg!(target_os = "windows") { ("cmd", "/C") } else { ("sh", "-c") };
+                entry.insert("command".to_string(), json!(shell));
+                entry.insert(
+                    "args".to_string(),
+                    json!([shell_arg, format!("{} {}", server.command, server.args.join(" "))]),
+                );
+            } else {
+                entry.insert("command".to_string(), json!(server.command));
+                entry.insert("args".to_string(), json!(server.args));
+            }
+            if !server.env.is_empty() {
+                entry.insert("env".to_string(), json!(server.env));
+            }
+            if let Some(cwd) = &server.cwd {
+                entry.insert("cwd".to_string(), json!(cwd));
+            }
+        }
+        transport => {
+            let url = server.url.clone().unwrap_or_default();
+            match client {
+                Client::ClaudeDesktop => {
+                    return Err("Claude Desktop only launches stdio servers; sync the gateway instead".to_string())
+                }
+                Client::VsCode => {
+                    let kind = if transport == Transport::Sse { "sse" } else { "http" };
+                    entry.insert("type".to_string(), json!(kind));
+                    entry.insert("url".to_string(), json!(url));
+                }
+                Client::Windsurf => {
+                    entry.insert("serverUrl".to_string(), json!(url));
+                }
+                Client::Cursor => {
+                    entry.insert("url".to_string(), json!(url));
+                }
+            }
+            if !server.headers.is_empty() {
+                entry.insert("headers".to_string(), json!(server.headers));
+            }
+        }
+    }
+    Ok(Value::Object(entry))
+}
+
+// An entry for the gateway at `url`. Clients that can't speak HTTP run the stdio helper.
+pub(crate) fn gateway_entry(client: Client, url: &str) -> Value {
+    match client {
+        Client::ClaudeDesktop => json!({
+            "command": gateway_helper().display().to_string(),
+            "args": ["--url", url],
+        }),
+        Client::VsCode => json!({ "type": "http", "url": url }),
+        Client::Windsurf => json!({ "serverUrl": url }),
+        Client::Cursor => json!({ "url": url }),
+    }
+}
+
+// The mcp-runner-gateway binary, installed next to the app's own
+fn gateway_helper() -> PathBuf {
+    let name = if cfg!(target_os = "windows") { "mcp-runner-gateway.exe" } else { "mcp-runner-gateway" };
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(name)))
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+// Puts `entries` into the client's server map at `path`, leaving every other key in the file
+// alone. Comments in JSONC files don't survive the rewrite, which the backup makes up for.
+pub(crate) fn sync(client: Client, path: &Path, entries: Vec<(String, Value)>, dry_run: bool) -> Result<SyncReport, String> {
+    let exists = path.exists();
+    let mut document = if exists {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        parse_jsonc(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?
+    } else {
+        json!({})
+    };
+    if !document.is_object() {
+        return Err(format!("{} does not contain a JSON object", path.display()));
+    }
+    let keys = servers_keys(client, &document);
+    let mut servers = &mut document;
+    for key in &keys {
+        let Some(object) = servers.as_object_mut() else {
+            return Err(format!("'{}' in {} is not an object", keys.join("."), path.display()));
+        };
+        servers = object.entry(*key).or_insert_with(|| json!({}));
+    }
+    let servers = servers
+        .as_object_mut()
+        .ok_or_else(|| format!("'{}' in {} is not an object", keys.join("."), path.display()))?;
+
+    let mut changes = Vec::new();
+    for (name, after) in entries {
+        let before = servers.insert(name.clone(), after.clone());
+        let change = match &before {
+            None => Change::Added,
+            Some(before) if *before == after => Change::Unchanged,
+            Some(_) => Change::Updated,
+        };
+        changes.push(EntryChange { name, change, before, after });
+    }
+
+    let mut report = SyncReport {
+        client,
+        path: path.display().to_string(),
+        backup: None,
+        written: false,
+        changes,
+    };
+    if dry_run || report.changes.iter().all(|change| change.change == Change::Unchanged) {
+        return Ok(report);
+    }
+
+    if exists {
+        let backup = PathBuf::from(format!("{}.{}.bak", path.display(), now_ms()));
+        fs::copy(path, &backup).map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+        report.backup = Some(backup.display().to_string());
+    } else if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    config_file::write_atomic(path, &content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    report.written = true;
+    Ok(report)
+}
+
+// VS Code and Cursor files are JSONC: comments and trailing commas are allowed
+pub(crate) fn parse_jsonc(content: &str) -> Result<Value, serde_json::Error> {
+    serde_json::from_str(&strip_jsonc(content))
+}
+
+fn strip_jsonc(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut i = 0;
+    let mut in_string = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+        match (c, chars.get(i + 1)) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+                i += 1;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            (',', _) => {
+                // Dropped when only whitespace or comments stand before the closing bracket
+                if !matches!(next_significant(&chars, i + 1), Some('}') | Some(']')) {
+                    out.push(c);
+                }
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+// The first character from `i` on that isn't whitespace or inside a comment
+fn next_significant(chars: &[char], mut i: usize) -> Option<char> {
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            (c, _) if c.is_whitespace() => i += 1,
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            (c, _) => return Some(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_jsonc_drops_comments() {
+        let content = "{\n  // line comment\n  \"a\": 1, /* block */ \"b\": 2\n}";
+        assert_eq!(parse_jsonc(content).unwrap(), json!({ "a": 1, "b": 2 }));
+    }
+
+    #[test]
+    fn strip_jsonc_leaves_strings_alone() {
+        let content = r#"{ "url": "http://example.com//x", "glob": "/* not a comment */", "quote": "a \" // b" }"#;
+        assert_eq!(
+            parse_jsonc(content).unwrap(),
+            json!({ "url": "http://example.com//x", "glob": "/* not a comment */", "quote": "a \" // b" })
+        );
+    }
+
+    #[test]
+    fn strip_jsonc_drops_trailing_commas() {
+        let content = "{ \"a\": [1, 2,], \"b\": { \"c\": 3, }, }";
+        assert_eq!(parse_jsonc(content).unwrap(), json!({ "a": [1, 2], "b": { "c": 3 } }));
+    }
+
+    #[test]
+    fn strip_jsonc_drops_trailing_commas_before_comments() {
+        let content = "{\n  \"a\": 1, // last one\n  /* nothing after */\n}";
+        assert_eq!(parse_jsonc(content).unwrap(), json!({ "a": 1 }));
+    }
+
+    #[test]
+    fn strip_jsonc_keeps_commas_between_values() {
+        assert_eq!(strip_jsonc("[1, /* two */ 2]"), "[1,  2]");
+    }
+
+    #[test]
+    fn strip_jsonc_survives_unterminated_comments() {
+        assert_eq!(strip_jsonc("{} /* open"), "{} ");
+        assert_eq!(strip_jsonc("{} // open"), "{} ");
+    }
+}
@@ -0,0 +1,149 @@
+// Reading and writing mcp-config.json. Writes go to a temp file that is renamed over the real
+// one, so a crash mid-write never leaves a truncated config, and the previous version is kept
+// as a timestamped backup first.
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::output::now_ms;
+use crate::{config_schema, Config};
+
+const FILE_NAME: &str = "mcp-config.json";
+
+// Backups kept per config file; the oldest go first
+const MAX_BACKUPS: usize = 10;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct BackupInfo {
+    name: String,
+    size_bytes: u64,
+    created_ms: u64,
+}
+
+// mcp-config.json in the app config directory
+pub(crate) fn default_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let app_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|_| "Failed to get app config directory".to_string())?;
+    Ok(app_dir.join(FILE_NAME))
+}
+
+// Backups live beside the config they were taken of
+fn backup_dir(path: &Path) -> PathBuf {
+    path.parent().unwrap_or_else(|| Path::new(".")).join("backups")
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "config".to_string())
+}
+
+pub(crate) fn read(path: &Path) -> Result<Config, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read config file: {}", e))?;
+    let value = serde_json::from_str(&content).map_err(|e| format!("Failed to parse config file: {}", e))?;
+    config_schema::parse(value)
+}
+
+// The default config, or an empty one if there isn't one yet
+pub(crate) fn read_default<R: Runtime>(app: &AppHandle<R>) -> Result<Config, String> {
+    let path = default_path(app)?;
+    if path.exists() {
+        read(&path)
+    } else {
+        Ok(Config::default())
+    }
+}
+
+pub(crate) fn write(path: &Path, config: &Config) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    if path.exists() {
+        if fs::read_to_string(path).is_ok_and(|current| current == content) {
+            return Ok(());
+        }
+        backup(path)?;
+    }
+
+    write_atomic(path, &content).map_err(|e| format!("Failed to write config file: {}", e))
+}
+
+// Writes `content` to a temp file beside `path` and renames it over `path`, so readers only
+// ever see the old file or the complete new one
+pub(crate) fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    name.push(".tmp");
+    let temp = path.with_file_name(name);
+    let result = File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+pub(crate) fn write_default<R: Runtime>(app: &AppHandle<R>, config: &Config) -> Result<(), String> {
+    write(&default_path(app)?, config)
+}
+
+// Copies the current file to backups/<stem>-<ms>.json and drops the oldest beyond MAX_BACKUPS
+fn backup(path: &Path) -> Result<(), String> {
+    let dir = backup_dir(path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    let target = dir.join(format!("{}-{}.json", stem(path), now_ms()));
+    fs::copy(path, &target).map_err(|e| format!("Failed to back up config file: {}", e))?;
+
+    let backups = list_backups(path)?;
+    for stale in backups.iter().skip(MAX_BACKUPS) {
+        let _ = fs::remove_file(dir.join(&stale.name));
+    }
+    Ok(())
+}
+
+// Backups of the config at `path`, newest first
+pub(crate) fn list_backups(path: &Path) -> Result<Vec<BackupInfo>, String> {
+    let dir = backup_dir(path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let prefix = format!("{}-", stem(path));
+
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read backup directory: {}", e))?;
+    let mut backups = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let created_ms = name
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix(".json"))
+            .and_then(|ms| ms.parse::<u64>().ok());
+        let Some(created_ms) = created_ms else {
+            continue;
+        };
+        let size_bytes = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        backups.push(BackupInfo { name, size_bytes, created_ms });
+    }
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_ms));
+    Ok(backups)
+}
+
+// Reads backup `name` of the config at `path`
+pub(crate) fn read_backup(path: &Path, name: &str) -> Result<Config, String> {
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid backup name '{}'", name));
+    }
+    let backup = backup_dir(path).join(name);
+    if !backup.exists() {
+        return Err(format!("Backup '{}' not found", name));
+    }
+    read(&backup)
+}
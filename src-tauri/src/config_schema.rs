@@ -0,0 +1,124 @@
+// Validation of mcp-config.json: the published JSON Schema for its shape, then the rules a
+// schema can't express (required commands, port clashes, dependencies, regexes). Every error
+// carries a JSON pointer to the offending value so the editor can point at it.
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use serde_json::Value;
+
+use crate::schema::{self, SchemaError};
+use crate::{dependencies, error_detection, remote, Config};
+
+pub(crate) const CONFIG_SCHEMA_JSON: &str = include_str!("../schemas/mcp-config.schema.json");
+
+pub(crate) static CONFIG_SCHEMA: LazyLock<Value> =
+    LazyLock::new(|| serde_json::from_str(CONFIG_SCHEMA_JSON).expect("bundled config schema is valid JSON"));
+
+// "/a/b" from path segments, escaped as RFC 6901 requires
+pub(crate) fn pointer(segments: &[&str]) -> String {
+    segments
+        .iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+fn error(segments: &[&str], message: impl Into<String>) -> SchemaError {
+    SchemaError {
+        path: pointer(segments),
+        message: message.into(),
+    }
+}
+
+// Everything wrong with a config document, empty when it is valid
+pub(crate) fn check(value: &Value) -> Vec<SchemaError> {
+    let errors = match schema::validate(&CONFIG_SCHEMA, value) {
+        Ok(errors) => errors,
+        Err(e) => return vec![error(&[], e)],
+    };
+    if !errors.is_empty() {
+        return errors;
+    }
+    match serde_json::from_value::<Config>(value.clone()) {
+        Ok(config) => check_rules(&config),
+        Err(e) => vec![error(&[], e.to_string())],
+    }
+}
+
+// Like `check` for config file text, with syntax errors reported by line and column
+pub(crate) fn check_text(content: &str) -> Vec<SchemaError> {
+    match serde_json::from_str::<Value>(content) {
+        Ok(value) => check(&value),
+        Err(e) => vec![error(&[], format!("Invalid JSON: {}", e))],
+    }
+}
+
+// The config in `value`, or every reason it isn't one
+pub(crate) fn parse(value: Value) -> Result<Config, String> {
+    let errors = check(&value);
+    if !errors.is_empty() {
+        return Err(format!("Invalid config: {}", schema::describe(&errors)));
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid config: {}", e))
+}
+
+fn check_rules(config: &Config) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    let mut ids: Vec<&String> = config.mcp_servers.keys().collect();
+    ids.sort();
+
+    // Which server (or the gateway) claimed each port first, in id order
+    let mut ports: HashMap<u16, String> = HashMap::new();
+    if let Some(gateway) = &config.gateway {
+        ports.insert(gateway.port, "the gateway".to_string());
+    }
+
+    let mut unknown_dependency = false;
+    for id in ids {
+        let server = &config.mcp_servers[id];
+        match server.transport() {
+            remote::Transport::Stdio if server.command.trim().is_empty() => {
+                errors.push(error(&["mcpServers", id, "command"], "must not be empty"));
+            }
+            remote::Transport::Sse | remote::Transport::StreamableHttp if server.url.is_none() => {
+                errors.push(error(&["mcpServers", id], "'url' is required for remote servers"));
+            }
+            _ => {}
+        }
+
+        if let Some(port) = server.port {
+            match ports.get(&port) {
+                Some(owner) => errors.push(error(
+                    &["mcpServers", id, "port"],
+                    format!("port {} is already used by {}", port, owner),
+                )),
+                None => {
+                    ports.insert(port, format!("'{}'", id));
+                }
+            }
+        }
+
+        for (index, dependency) in server.depends_on.iter().enumerate() {
+            if !config.mcp_servers.contains_key(dependency) {
+                unknown_dependency = true;
+                errors.push(error(
+                    &["mcpServers", id, "dependsOn", &index.to_string()],
+                    format!("unknown server '{}'", dependency),
+                ));
+            }
+        }
+
+        if let Some(detection) = &server.error_detection {
+            if let Err(e) = error_detection::ErrorDetector::new(detection) {
+                errors.push(error(&["mcpServers", id, "errorDetection"], e));
+            }
+        }
+    }
+
+    // Cycles only make sense to look for once every dependency exists
+    if !unknown_dependency {
+        if let Err(e) = dependencies::validate(config) {
+            errors.push(error(&["mcpServers"], e));
+        }
+    }
+    errors
+}
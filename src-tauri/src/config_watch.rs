@@ -0,0 +1,257 @@
+// Hot reload of the active config file. A background thread polls its modification time and
+// size; when they change the file is re-parsed and, if it differs from what the store holds,
+// swapped in and announced with a `config-changed` event.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::schema::{self, SchemaError};
+use crate::{
+    config_schema, current_info, events, spawn_server, stop_server, wait_until_stopped, Config, ConfigStore,
+    MCPServerConfig,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Extra time past a server's stop timeout before a restart gives up on it
+const RESTART_STOP_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct HotReloadConfig {
+    // Pick up edits made to the config file outside the app
+    #[serde(default = "default_enabled", skip_serializing_if = "is_default_enabled")]
+    pub(crate) enabled: bool,
+    // Restart running servers whose launch settings (command, args, env, ...) changed
+    #[serde(rename = "restartChanged", default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) restart_changed: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_default_enabled(value: &bool) -> bool {
+    *value == default_enabled()
+}
+
+impl Default for HotReloadConfig {
+    fn default() -> Self {
+        HotReloadConfig {
+            enabled: default_enabled(),
+            restart_changed: false,
+        }
+    }
+}
+
+// Payload of the config-changed event
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub(crate) struct ConfigDiff {
+    path: String,
+    added: Vec<String>,
+    removed: Vec<String>,
+    modified: Vec<String>,
+    // Running servers being restarted because their launch settings changed
+    restarted: Vec<String>,
+}
+
+// Payload of the config-invalid event
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ConfigInvalid {
+    path: String,
+    errors: Vec<SchemaError>,
+}
+
+type Stamp = (Option<SystemTime>, u64);
+
+struct WatchState {
+    path: Option<PathBuf>,
+    stamp: Option<Stamp>,
+}
+
+// Store for the config file being watched and how it looked when last seen
+pub(crate) struct ConfigWatchStore {
+    inner: Mutex<WatchState>,
+}
+
+impl ConfigWatchStore {
+    pub(crate) fn new() -> Self {
+        ConfigWatchStore {
+            inner: Mutex::new(WatchState { path: None, stamp: None }),
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| (metadata.modified().ok(), metadata.len()))
+}
+
+// Makes `path` the active config file, as it is right now
+pub(crate) fn watch<R: Runtime>(app: &AppHandle<R>, path: PathBuf) {
+    let store = app.state::<ConfigWatchStore>();
+    let Ok(mut state) = store.inner.lock() else {
+        return;
+    };
+    state.stamp = stamp(&path);
+    state.path = Some(path);
+}
+
+pub(crate) fn spawn<R: Runtime>(app: AppHandle<R>) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let changed = {
+            let store = app.state::<ConfigWatchStore>();
+            let Ok(mut state) = store.inner.lock() else {
+                continue;
+            };
+            let Some(path) = state.path.clone() else {
+                continue;
+            };
+            let current = stamp(&path);
+            // A deleted file isn't a change to apply; the store keeps the last config
+            if current.is_none() || current == state.stamp {
+                continue;
+            }
+            state.stamp = current;
+            path
+        };
+        reload(&app, &changed);
+    });
+}
+
+fn reload<R: Runtime>(app: &AppHandle<R>, path: &Path) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Config: Ignoring change to {}: {}", path.display(), e);
+            return;
+        }
+    };
+    // An invalid edit leaves the running config alone, but whoever made it needs to hear why
+    let errors = config_schema::check_text(&content);
+    if !errors.is_empty() {
+        eprintln!("Config: Ignoring change to {}: {}", path.display(), schema::describe(&errors));
+        events::emit_config_invalid(app, &ConfigInvalid { path: path.display().to_string(), errors });
+        return;
+    }
+    let config: Config = match serde_json::from_str(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Config: Ignoring change to {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    let (mut diff, changed) = {
+        let config_store = app.state::<ConfigStore>();
+        let Ok(mut store) = config_store.0.lock() else {
+            return;
+        };
+        let hot_reload = store.hot_reload.clone().unwrap_or_default();
+        // Our own writes land here too, with the store already up to date
+        if !hot_reload.enabled || serde_json::to_value(&*store).ok() == serde_json::to_value(&config).ok() {
+            return;
+        }
+
+        let mut diff = diff(&store, &config);
+        diff.path = path.display().to_string();
+        let changed: Vec<(String, MCPServerConfig)> = if hot_reload.restart_changed {
+            diff.modified
+                .iter()
+                .filter(|id| launch_settings(&store.mcp_servers[*id]) != launch_settings(&config.mcp_servers[*id]))
+                .map(|id| (id.clone(), config.mcp_servers[id].clone()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        *store = config;
+        (diff, changed)
+    };
+
+    // Servers that aren't running pick up their new settings on the next start anyway
+    let restart: Vec<(String, MCPServerConfig)> = changed
+        .into_iter()
+        .filter(|(id, _)| current_info(app, id).ok().flatten().is_some_and(|info| info.is_running))
+        .collect();
+    diff.restarted = restart.iter().map(|(id, _)| id.clone()).collect();
+
+    println!(
+        "Config: Reloaded {} (added {}, removed {}, modified {})",
+        diff.path,
+        diff.added.len(),
+        diff.removed.len(),
+        diff.modified.len()
+    );
+    events::emit_config_changed(app, &diff);
+
+    for (id, server_config) in restart {
+        let app = app.clone();
+        thread::spawn(move || {
+            if let Err(e) = restart_server(&app, &id, server_config) {
+                eprintln!("Config: Failed to restart {}: {}", id, e);
+            }
+        });
+    }
+}
+
+// Emits config-changed for a config the app replaced itself, without restarting anything
+pub(crate) fn announce<R: Runtime>(app: &AppHandle<R>, path: &Path, old: &Config, new: &Config) {
+    let mut diff = diff(old, new);
+    diff.path = path.display().to_string();
+    events::emit_config_changed(app, &diff);
+}
+
+fn diff(old: &Config, new: &Config) -> ConfigDiff {
+    let mut diff = ConfigDiff::default();
+    for (id, server) in &new.mcp_servers {
+        match old.mcp_servers.get(id) {
+            None => diff.added.push(id.clone()),
+            Some(previous) if serde_json::to_value(previous).ok() != serde_json::to_value(server).ok() => {
+                diff.modified.push(id.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .mcp_servers
+        .keys()
+        .filter(|id| !new.mcp_servers.contains_key(*id))
+        .cloned()
+        .collect();
+    diff.added.sort();
+    diff.removed.sort();
+    diff.modified.sort();
+    diff
+}
+
+// What a running process or connection was created from; other settings apply without a restart
+fn launch_settings(server: &MCPServerConfig) -> Value {
+    json!({
+        "command": server.command,
+        "args": server.args,
+        "env": server.env,
+        "cwd": server.cwd,
+        "shell": server.shell,
+        "transport": server.transport(),
+        "url": server.url,
+        "headers": server.headers,
+        "inheritEnv": server.inherit_env,
+        "envAllowlist": server.env_allowlist,
+        "envDenylist": server.env_denylist,
+    })
+}
+
+fn restart_server<R: Runtime>(app: &AppHandle<R>, id: &str, server_config: MCPServerConfig) -> Result<(), String> {
+    println!("Config: Restarting {} with its new settings", id);
+    let timeout = Duration::from_millis(server_config.stop_timeout_ms) + RESTART_STOP_GRACE;
+    stop_server(app, id)?;
+    wait_until_stopped(app, id, timeout)?;
+    spawn_server(app, id, server_config, 0).map(|_| ())
+}
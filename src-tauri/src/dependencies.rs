@@ -0,0 +1,161 @@
+// Ordering of servers by their `dependsOn` lists
+use std::collections::HashSet;
+
+use crate::Config;
+
+// Returns `ids` plus everything they transitively depend on, with every server
+// listed after its dependencies. Fails on unknown servers and dependency cycles.
+pub(crate) fn start_order(config: &Config, ids: &[String]) -> Result<Vec<String>, String> {
+    let mut order = Vec::new();
+    let mut done = HashSet::new();
+    for id in ids {
+        visit(config, id, &mut Vec::new(), &mut done, &mut order)?;
+    }
+    Ok(order)
+}
+
+// Returns `ids` plus every server that transitively depends on them, with every server
+// listed before its dependencies. Servers the group depends on are left out.
+pub(crate) fn stop_order(config: &Config, ids: &[String]) -> Result<Vec<String>, String> {
+    let mut group: HashSet<&str> = HashSet::new();
+    let mut pending: Vec<&str> = Vec::new();
+    for id in ids {
+        if !config.mcp_servers.contains_key(id) {
+            return Err(format!("Server '{}' not found in configuration", id));
+        }
+        pending.push(id);
+    }
+    while let Some(id) = pending.pop() {
+        if !group.insert(id) {
+            continue;
+        }
+        pending.extend(
+            config
+                .mcp_servers
+                .iter()
+                .filter(|(_, server)| server.depends_on.iter().any(|dependency| dependency == id))
+                .map(|(dependent, _)| dependent.as_str()),
+        );
+    }
+
+    let mut members: Vec<String> = group.iter().map(|id| id.to_string()).collect();
+    members.sort();
+    let mut order = start_order(config, &members)?;
+    order.retain(|id| group.contains(id.as_str()));
+    order.reverse();
+    Ok(order)
+}
+
+// Checks that every `dependsOn` entry names a configured server and that there are no cycles
+pub(crate) fn validate(config: &Config) -> Result<(), String> {
+    let mut ids: Vec<String> = config.mcp_servers.keys().cloned().collect();
+    ids.sort();
+    start_order(config, &ids).map(|_| ())
+}
+
+fn visit(
+    config: &Config,
+    id: &str,
+    path: &mut Vec<String>,
+    done: &mut HashSet<String>,
+    order: &mut Vec<String>,
+) -> Result<(), String> {
+    if done.contains(id) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|visiting| visiting == id) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(id.to_string());
+        return Err(format!("Dependency cycle: {}", cycle.join(" -> ")));
+    }
+
+    let server = config.mcp_servers.get(id).ok_or_else(|| match path.last() {
+        Some(dependent) => format!(
+            "Server '{}' depends on '{}', which is not in the configuration",
+            dependent, id
+        ),
+        None => format!("Server '{}' not found in configuration", id),
+    })?;
+
+    path.push(id.to_string());
+    for dependency in &server.depends_on {
+        visit(config, dependency, path, done, order)?;
+    }
+    path.pop();
+
+    done.insert(id.to_string());
+    order.push(id.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Servers named after their dependencies: ("app", ["proxy"]) depends on "proxy"
+    fn config(servers: &[(&str, &[&str])]) -> Config {
+        let servers: serde_json::Map<String, serde_json::Value> = servers
+            .iter()
+            .map(|(id, depends_on)| (id.to_string(), json!({ "command": "run", "dependsOn": depends_on })))
+            .collect();
+        serde_json::from_value(json!({ "mcpServers": servers })).unwrap()
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn start_order_puts_dependencies_first() {
+        let config = config(&[("app", &["proxy", "db"]), ("proxy", &["db"]), ("db", &[]), ("other", &[])]);
+        assert_eq!(start_order(&config, &ids(&["app"])).unwrap(), ids(&["db", "proxy", "app"]));
+    }
+
+    #[test]
+    fn start_order_lists_shared_dependencies_once() {
+        let config = config(&[("a", &["db"]), ("b", &["db"]), ("db", &[])]);
+        assert_eq!(start_order(&config, &ids(&["a", "b"])).unwrap(), ids(&["db", "a", "b"]));
+    }
+
+    #[test]
+    fn start_order_rejects_cycles() {
+        let config = config(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"])]);
+        assert_eq!(start_order(&config, &ids(&["a"])).unwrap_err(), "Dependency cycle: a -> b -> c -> a");
+    }
+
+    #[test]
+    fn start_order_rejects_unknown_servers() {
+        let config = config(&[("app", &["missing"])]);
+        assert_eq!(
+            start_order(&config, &ids(&["app"])).unwrap_err(),
+            "Server 'app' depends on 'missing', which is not in the configuration"
+        );
+        assert_eq!(
+            start_order(&config, &ids(&["nope"])).unwrap_err(),
+            "Server 'nope' not found in configuration"
+        );
+    }
+
+    #[test]
+    fn stop_order_puts_dependents_first_and_leaves_dependencies() {
+        let config = config(&[("app", &["proxy"]), ("proxy", &["db"]), ("db", &[]), ("cli", &["proxy"])]);
+        let order = stop_order(&config, &ids(&["proxy"])).unwrap();
+        assert_eq!(order.len(), 3);
+        assert_eq!(order.last().map(String::as_str), Some("proxy"));
+        assert!(order.contains(&"app".to_string()) && order.contains(&"cli".to_string()));
+        assert_eq!(stop_order(&config, &ids(&["app"])).unwrap(), ids(&["app"]));
+    }
+
+    #[test]
+    fn stop_order_rejects_unknown_servers() {
+        let config = config(&[("app", &[])]);
+        assert_eq!(stop_order(&config, &ids(&["nope"])).unwrap_err(), "Server 'nope' not found in configuration");
+    }
+
+    #[test]
+    fn validate_checks_every_server() {
+        assert!(validate(&config(&[("a", &["b"]), ("b", &[])])).is_ok());
+        assert!(validate(&config(&[("a", &["a"])])).is_err());
+    }
+}
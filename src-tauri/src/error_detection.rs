@@ -0,0 +1,104 @@
+// Decides which stderr lines count as errors. MCP stdio servers log everything to stderr
+// (stdout carries JSON-RPC), so stderr output alone says nothing about failure.
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+// Levels at or above error in the usual logging libraries
+const ERROR_LEVELS: &[&str] = &["error", "err", "fatal", "critical", "crit", "panic", "emerg", "alert"];
+
+// pino/bunyan write numeric levels; 50 is error, 60 fatal
+const NUMERIC_ERROR_LEVEL: f64 = 50.0;
+
+// "[ERROR] ...", "level=error ...", "ERROR: ...", "2024-01-01T00:00:00Z ERROR crate: ..."
+static TEXT_LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?x)
+        \[(?i:error|err|fatal|critical|crit|panic)\]
+        | \b(?i:level|lvl|severity)=["']?(?i:error|err|fatal|critical|crit|panic)\b
+        | (?:^|\s)(?:ERROR|FATAL|CRITICAL|PANIC)(?::|\s|$)
+        "#,
+    )
+    .expect("built-in log level pattern is valid")
+});
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ErrorDetectionConfig {
+    // Recognise error levels in common text and JSON log formats
+    #[serde(rename = "parseLogLevel", default = "default_parse_log_level", skip_serializing_if = "is_default_parse_log_level")]
+    parse_log_level: bool,
+    // Extra regexes; a matching line is an error
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    patterns: Vec<String>,
+    // Regexes for lines that are never errors, checked before everything else
+    #[serde(rename = "ignorePatterns", default, skip_serializing_if = "Vec::is_empty")]
+    ignore_patterns: Vec<String>,
+}
+
+fn default_parse_log_level() -> bool {
+    true
+}
+
+fn is_default_parse_log_level(value: &bool) -> bool {
+    *value == default_parse_log_level()
+}
+
+impl Default for ErrorDetectionConfig {
+    fn default() -> Self {
+        ErrorDetectionConfig {
+            parse_log_level: default_parse_log_level(),
+            patterns: Vec::new(),
+            ignore_patterns: Vec::new(),
+        }
+    }
+}
+
+pub(crate) struct ErrorDetector {
+    parse_log_level: bool,
+    patterns: Vec<Regex>,
+    ignore_patterns: Vec<Regex>,
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid error pattern '{}': {}", pattern, e)))
+        .collect()
+}
+
+impl ErrorDetector {
+    pub(crate) fn new(config: &ErrorDetectionConfig) -> Result<Self, String> {
+        Ok(ErrorDetector {
+            parse_log_level: config.parse_log_level,
+            patterns: compile(&config.patterns)?,
+            ignore_patterns: compile(&config.ignore_patterns)?,
+        })
+    }
+
+    pub(crate) fn is_error(&self, line: &str) -> bool {
+        if self.ignore_patterns.iter().any(|pattern| pattern.is_match(line)) {
+            return false;
+        }
+        if self.patterns.iter().any(|pattern| pattern.is_match(line)) {
+            return true;
+        }
+        self.parse_log_level && has_error_level(line)
+    }
+}
+
+fn has_error_level(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('{') {
+        if let Ok(serde_json::Value::Object(fields)) = serde_json::from_str::<serde_json::Value>(trimmed) {
+            return ["level", "severity", "lvl"]
+                .iter()
+                .filter_map(|key| fields.get(*key))
+                .any(|level| match level {
+                    serde_json::Value::String(level) => ERROR_LEVELS.contains(&level.to_ascii_lowercase().as_str()),
+                    serde_json::Value::Number(level) => level.as_f64().is_some_and(|level| level >= NUMERIC_ERROR_LEVEL),
+                    _ => false,
+                });
+        }
+    }
+    TEXT_LEVEL.is_match(line)
+}
@@ -0,0 +1,77 @@
+// Events pushed to the frontend so it doesn't have to poll for output and status
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::config_watch::{ConfigDiff, ConfigInvalid};
+use crate::inventory::ListKind;
+use crate::output::LogRecord;
+use crate::CommandInfo;
+
+pub(crate) const SERVER_OUTPUT_EVENT: &str = "server-output";
+pub(crate) const SERVER_STATUS_EVENT: &str = "server-status";
+pub(crate) const SERVER_INVENTORY_EVENT: &str = "server-inventory-changed";
+pub(crate) const CONFIG_CHANGED_EVENT: &str = "config-changed";
+pub(crate) const CONFIG_INVALID_EVENT: &str = "config-invalid";
+
+// Status events are numbered across all servers; a jump means the listener missed some
+// and should re-read every server's info
+static STATUS_SEQ: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, serde::Serialize)]
+struct OutputEvent<'a> {
+    id: &'a str,
+    // Same session and seq as get_command_output_since, so a listener that sees a gap
+    // can fill it from there
+    session: u64,
+    #[serde(flatten)]
+    record: &'a LogRecord,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct StatusEvent<'a> {
+    seq: u64,
+    #[serde(flatten)]
+    info: &'a CommandInfo,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct InventoryEvent<'a> {
+    id: &'a str,
+    kind: ListKind,
+}
+
+pub(crate) fn emit_output<R: Runtime>(app: &AppHandle<R>, id: &str, session: u64, record: &LogRecord) {
+    let event = OutputEvent { id, session, record };
+    if let Err(e) = app.emit(SERVER_OUTPUT_EVENT, event) {
+        eprintln!("Events: Failed to emit output for {}: {}", id, e);
+    }
+}
+
+pub(crate) fn emit_status<R: Runtime>(app: &AppHandle<R>, info: &CommandInfo) {
+    let seq = STATUS_SEQ.fetch_add(1, Ordering::SeqCst);
+    if let Err(e) = app.emit(SERVER_STATUS_EVENT, StatusEvent { seq, info }) {
+        eprintln!("Events: Failed to emit status for {}: {}", info.id, e);
+    }
+}
+
+// Sent when a server's tool/resource/prompt list changed; cached lists are already refetched
+pub(crate) fn emit_inventory<R: Runtime>(app: &AppHandle<R>, id: &str, kind: ListKind) {
+    if let Err(e) = app.emit(SERVER_INVENTORY_EVENT, InventoryEvent { id, kind }) {
+        eprintln!("Events: Failed to emit inventory change for {}: {}", id, e);
+    }
+}
+
+// Sent when the config file was edited outside the app and the new version is in the store
+pub(crate) fn emit_config_changed<R: Runtime>(app: &AppHandle<R>, diff: &ConfigDiff) {
+    if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, diff) {
+        eprintln!("Events: Failed to emit config change: {}", e);
+    }
+}
+
+// Sent when the config file was edited outside the app into something that doesn't validate
+pub(crate) fn emit_config_invalid<R: Runtime>(app: &AppHandle<R>, invalid: &ConfigInvalid) {
+    if let Err(e) = app.emit(CONFIG_INVALID_EVENT, invalid) {
+        eprintln!("Events: Failed to emit invalid config: {}", e);
+    }
+}
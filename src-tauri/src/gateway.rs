@@ -0,0 +1,307 @@
+// One MCP endpoint in front of every running server. Speaks Streamable HTTP on localhost
+// (the mcp-runner-gateway binary bridges stdio clients to it) and forwards each request to
+// the server it belongs to. Tool and prompt names, and resource names, are prefixed with
+// the server id so servers can't collide.
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tiny_http::{Method, Request, Server};
+
+use crate::http::{self, HttpResponse, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND};
+use crate::inventory;
+use crate::mcp::{self, SUPPORTED_PROTOCOL_VERSIONS};
+use crate::tool_call::TOOL_CALL_TIMEOUT;
+use crate::{mcp_channel, CommandStatus, ProcessStore};
+
+// Between the server id and the server's own name, e.g. "github__create_issue"
+const NAME_SEPARATOR: &str = "__";
+
+const ENDPOINT_PATH: &str = "/mcp";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct GatewayConfig {
+    // Start the gateway when the app launches
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) enabled: bool,
+    #[serde(default = "default_port", skip_serializing_if = "is_default_port")]
+    pub(crate) port: u16,
+}
+
+pub(crate) fn default_port() -> u16 {
+    7420
+}
+
+fn is_default_port(port: &u16) -> bool {
+    *port == default_port()
+}
+
+pub(crate) fn endpoint_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}{}", port, ENDPOINT_PATH)
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct GatewayStatus {
+    running: bool,
+    pub(crate) port: Option<u16>,
+    url: Option<String>,
+}
+
+struct RunningGateway {
+    server: Arc<Server>,
+    port: u16,
+}
+
+// Store for the gateway's HTTP listener while it runs
+pub(crate) struct GatewayStore {
+    inner: Mutex<Option<RunningGateway>>,
+}
+
+impl GatewayStore {
+    pub(crate) fn new() -> Self {
+        GatewayStore { inner: Mutex::new(None) }
+    }
+}
+
+pub(crate) fn status<R: Runtime>(app: &AppHandle<R>) -> Result<GatewayStatus, String> {
+    let store = app.state::<GatewayStore>();
+    let running = store.inner.lock().map_err(|e| e.to_string())?;
+    Ok(match running.as_ref() {
+        Some(gateway) => GatewayStatus {
+            running: true,
+            port: Some(gateway.port),
+            url: Some(endpoint_url(gateway.port)),
+        },
+        None => GatewayStatus { running: false, port: None, url: None },
+    })
+}
+
+pub(crate) fn start<R: Runtime>(app: &AppHandle<R>, port: u16) -> Result<GatewayStatus, String> {
+    {
+        let store = app.state::<GatewayStore>();
+        let mut running = store.inner.lock().map_err(|e| e.to_string())?;
+        if let Some(gateway) = running.as_ref() {
+            if gateway.port == port {
+                drop(running);
+                return status(app);
+            }
+            return Err(format!("Gateway is already running on port {}", gateway.port));
+        }
+
+        // Loopback only: the gateway hands out every server's tools without authentication
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| format!("Failed to start gateway on port {}: {}", port, e))?;
+        let server = Arc::new(server);
+        *running = Some(RunningGateway { server: Arc::clone(&server), port });
+
+        let app = app.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                // Tool calls can take a while; don't make other clients wait behind them
+                let app = app.clone();
+                thread::spawn(move || handle_http(&app, request));
+            }
+            println!("Gateway: Listener on port {} stopped", port);
+        });
+    }
+    println!("Gateway: Listening on http://127.0.0.1:{}{}", port, ENDPOINT_PATH);
+    status(app)
+}
+
+pub(crate) fn stop<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let store = app.state::<GatewayStore>();
+    let mut running = store.inner.lock().map_err(|e| e.to_string())?;
+    if let Some(gateway) = running.take() {
+        gateway.server.unblock();
+    }
+    Ok(())
+}
+
+fn handle_http<R: Runtime>(app: &AppHandle<R>, mut request: Request) {
+    let response = http_response(app, &mut request);
+    if let Err(e) = request.respond(response) {
+        eprintln!("Gateway: Failed to send response: {}", e);
+    }
+}
+
+fn http_response<R: Runtime>(app: &AppHandle<R>, request: &mut Request) -> HttpResponse {
+    if http::path(request) != ENDPOINT_PATH {
+        return http::text_response(404, "Not found");
+    }
+    if !http::origin_allowed(request) {
+        return http::text_response(403, "Forbidden origin");
+    }
+    // No server-initiated stream: everything is answered in the POST response
+    if *request.method() != Method::Post {
+        return http::text_response(405, "Method not allowed").with_header(http::header("Allow", "POST"));
+    }
+
+    let message = match http::read_json(request) {
+        Ok(message) => message,
+        Err(response) => return response,
+    };
+    match http::handle_batch(message, |message| handle_message(app, message)) {
+        Some(reply) => http::json_response(200, &reply),
+        // Only notifications or responses were sent
+        None => http::accepted(),
+    }
+}
+
+// Answers one JSON-RPC message; None for notifications, which get no reply
+fn handle_message<R: Runtime>(app: &AppHandle<R>, message: Value) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        // A response to something we never send, or garbage
+        return id.map(|id| http::error_response(id, INVALID_REQUEST, "Expected a JSON-RPC request"));
+    };
+    let id = id?;
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    match dispatch(app, method, &params) {
+        Ok(result) => Some(json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+        Err((code, message)) => Some(http::error_response(id, code, &message)),
+    }
+}
+
+fn dispatch<R: Runtime>(app: &AppHandle<R>, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(Value::as_str).unwrap_or_default();
+            let protocol_version = if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) {
+                requested
+            } else {
+                SUPPORTED_PROTOCOL_VERSIONS[0]
+            };
+            Ok(json!({
+                "protocolVersion": protocol_version,
+                "capabilities": { "tools": {}, "resources": {}, "prompts": {} },
+                "serverInfo": { "name": "mcp-server-runner-gateway", "version": env!("CARGO_PKG_VERSION") },
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({
+            "tools": collect(app, |channel, session| inventory::tools(channel, session, false)),
+        })),
+        "prompts/list" => Ok(json!({
+            "prompts": collect(app, |channel, session| inventory::prompts(channel, session, false)),
+        })),
+        "resources/list" => Ok(json!({
+            "resources": collect(app, |channel, session| inventory::resources(channel, session, false)),
+        })),
+        "resources/templates/list" => Ok(json!({
+            "resourceTemplates": collect(app, |channel, session| inventory::resource_templates(channel, session, false)),
+        })),
+        "tools/call" => forward_named(app, "tools/call", params, TOOL_CALL_TIMEOUT),
+        "prompts/get" => forward_named(app, "prompts/get", params, mcp::REQUEST_TIMEOUT),
+        "resources/read" => read_resource(app, params),
+        _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    }
+}
+
+// Servers that are up, in a stable order
+fn running_ids<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let process_store = app.state::<ProcessStore>();
+    let Ok(store) = process_store.inner.lock() else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = store
+        .iter()
+        .filter(|(_, (_, info))| {
+            matches!(
+                info.status,
+                CommandStatus::Running | CommandStatus::Healthy | CommandStatus::Unhealthy { .. }
+            )
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+// Gathers one list from every running server, renaming each item "<server id>__<name>".
+// Servers that can't answer (not MCP over stdio, lacking the capability) are left out.
+fn collect<R: Runtime, T: Serialize>(
+    app: &AppHandle<R>,
+    list: impl Fn(&crate::rpc::RpcChannel, &mcp::ServerSession) -> Result<Vec<T>, String>,
+) -> Vec<Value> {
+    let mut items = Vec::new();
+    for id in running_ids(app) {
+        let listed = mcp_channel(app, &id, mcp::REQUEST_TIMEOUT).and_then(|(channel, session)| list(&channel, &session));
+        let Ok(listed) = listed else {
+            continue;
+        };
+        for item in listed {
+            let Ok(mut item) = serde_json::to_value(item) else {
+                continue;
+            };
+            if let Some(name) = item.get("name").and_then(Value::as_str) {
+                item["name"] = json!(format!("{}{}{}", id, NAME_SEPARATOR, name));
+            }
+            items.push(item);
+        }
+    }
+    items
+}
+
+// Sends a request whose `name` param is namespaced to the server it names, with the prefix removed
+fn forward_named<R: Runtime>(
+    app: &AppHandle<R>,
+    method: &str,
+    params: &Value,
+    timeout: std::time::Duration,
+) -> Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| (INVALID_PARAMS, "Missing 'name'".to_string()))?;
+
+    // Server ids may themselves contain the separator, so prefer the longest id that fits
+    let (id, inner_name) = running_ids(app)
+        .into_iter()
+        .filter_map(|id| {
+            let inner = name.strip_prefix(id.as_str())?.strip_prefix(NAME_SEPARATOR)?.to_string();
+            Some((id, inner))
+        })
+        .max_by_key(|(id, _)| id.len())
+        .ok_or_else(|| (INVALID_PARAMS, format!("No running server provides '{}'", name)))?;
+
+    let (channel, _) = mcp_channel(app, &id, mcp::REQUEST_TIMEOUT).map_err(|e| (INTERNAL_ERROR, e))?;
+    let mut params = params.clone();
+    params["name"] = json!(inner_name);
+    channel.request(method, params, timeout).map_err(|e| (INTERNAL_ERROR, e))
+}
+
+// URIs aren't renamed, so a read goes to the server that listed the URI, or failing that to
+// each server offering resources in turn (covers URIs built from templates)
+fn read_resource<R: Runtime>(app: &AppHandle<R>, params: &Value) -> Result<Value, (i64, String)> {
+    let uri = params
+        .get("uri")
+        .and_then(Value::as_str)
+        .ok_or_else(|| (INVALID_PARAMS, "Missing 'uri'".to_string()))?;
+
+    let mut candidates = Vec::new();
+    for id in running_ids(app) {
+        let Ok((channel, session)) = mcp_channel(app, &id, mcp::REQUEST_TIMEOUT) else {
+            continue;
+        };
+        let Ok(resources) = inventory::resources(&channel, &session, false) else {
+            continue;
+        };
+        if resources.iter().any(|resource| resource.uri == uri) {
+            candidates.insert(0, channel);
+        } else {
+            candidates.push(channel);
+        }
+    }
+
+    let mut last_error = format!("No running server provides '{}'", uri);
+    for channel in candidates {
+        match channel.request("resources/read", params.clone(), mcp::REQUEST_TIMEOUT) {
+            Ok(result) => return Ok(result),
+            Err(e) => last_error = e,
+        }
+    }
+    Err((INVALID_PARAMS, last_error))
+}
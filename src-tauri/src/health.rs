@@ -0,0 +1,210 @@
+// Readiness and liveness checks for running servers
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::json;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::output::Stream;
+use crate::{append_output, events, kill_process, mcp_channel, CommandStatus, ProcessStore};
+
+// How a server is probed
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum HealthProbe {
+    // Open a TCP connection to the server's `port`
+    Tcp,
+    // GET `url` (default http://127.0.0.1:<port>/) and expect a non-error status
+    Http {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    // MCP `initialize` handshake over stdio, then `ping` on every later check
+    Mcp,
+}
+
+fn default_interval_ms() -> u64 {
+    10_000
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_initial_delay_ms() -> u64 {
+    1000
+}
+
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+fn is_default_interval_ms(value: &u64) -> bool {
+    *value == default_interval_ms()
+}
+
+fn is_default_timeout_ms(value: &u64) -> bool {
+    *value == default_timeout_ms()
+}
+
+fn is_default_initial_delay_ms(value: &u64) -> bool {
+    *value == default_initial_delay_ms()
+}
+
+fn is_default_failure_threshold(value: &u32) -> bool {
+    *value == default_failure_threshold()
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct HealthCheckConfig {
+    #[serde(flatten)]
+    pub(crate) probe: HealthProbe,
+    #[serde(rename = "intervalMs", default = "default_interval_ms", skip_serializing_if = "is_default_interval_ms")]
+    pub(crate) interval_ms: u64,
+    #[serde(rename = "timeoutMs", default = "default_timeout_ms", skip_serializing_if = "is_default_timeout_ms")]
+    pub(crate) timeout_ms: u64,
+    #[serde(
+        rename = "initialDelayMs",
+        default = "default_initial_delay_ms",
+        skip_serializing_if = "is_default_initial_delay_ms"
+    )]
+    pub(crate) initial_delay_ms: u64,
+    // Consecutive failures before the server is reported Unhealthy
+    #[serde(
+        rename = "failureThreshold",
+        default = "default_failure_threshold",
+        skip_serializing_if = "is_default_failure_threshold"
+    )]
+    pub(crate) failure_threshold: u32,
+    // Kill an Unhealthy server so its restart policy can bring it back
+    #[serde(rename = "restartWhenUnhealthy", default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) restart_when_unhealthy: bool,
+}
+
+// Probes `id` on its own thread for as long as `pid` is the process running it
+pub(crate) fn spawn_monitor<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    pid: u32,
+    port: Option<u16>,
+    check: HealthCheckConfig,
+) {
+    thread::spawn(move || {
+        let timeout = Duration::from_millis(check.timeout_ms);
+        let mut failures = 0u32;
+        thread::sleep(Duration::from_millis(check.initial_delay_ms));
+
+        loop {
+            if !is_current(&app, &id, pid) {
+                println!("Health: {} is no longer running, stopping checks.", id);
+                return;
+            }
+
+            let result = match &check.probe {
+                HealthProbe::Tcp => probe_tcp(port, timeout),
+                HealthProbe::Http { url } => probe_http(url.as_deref(), port, timeout),
+                HealthProbe::Mcp => probe_mcp(&app, &id, timeout),
+            };
+
+            let status = match result {
+                Ok(()) => {
+                    failures = 0;
+                    Some(CommandStatus::Healthy)
+                }
+                Err(message) => {
+                    failures += 1;
+                    println!("Health: Check {} failed for {}: {}", failures, id, message);
+                    (failures >= check.failure_threshold)
+                        .then_some(CommandStatus::Unhealthy { failures, message })
+                }
+            };
+
+            if let Some(status) = status {
+                let unhealthy = matches!(status, CommandStatus::Unhealthy { .. });
+                if !set_status(&app, &id, pid, status) {
+                    return;
+                }
+                // Only kill once per bad streak; the monitor thread takes it from there
+                if unhealthy && check.restart_when_unhealthy && failures == check.failure_threshold {
+                    append_output(&app, &id, Stream::System, format!(
+                        "Health check failed {} times in a row, killing the process",
+                        failures
+                    ));
+                    if let Err(e) = kill_process(pid) {
+                        eprintln!("Health: Failed to kill unhealthy {}: {}", id, e);
+                    }
+                }
+            }
+
+            thread::sleep(Duration::from_millis(check.interval_ms));
+        }
+    });
+}
+
+fn is_current<R: Runtime>(app: &AppHandle<R>, id: &str, pid: u32) -> bool {
+    let process_store = app.state::<ProcessStore>();
+    let store = process_store.inner.lock();
+    store.is_ok_and(|store| {
+        store
+            .get(id)
+            .is_some_and(|(_, info)| info.process_id == Some(pid))
+    })
+}
+
+// Records a probe result unless the process has moved on to stopping, exiting or restarting.
+// Returns false once the process is no longer the one being checked.
+fn set_status<R: Runtime>(app: &AppHandle<R>, id: &str, pid: u32, status: CommandStatus) -> bool {
+    let process_store = app.state::<ProcessStore>();
+    let Ok(mut store) = process_store.inner.lock() else {
+        return false;
+    };
+    let Some((_, info)) = store.get_mut(id) else {
+        return false;
+    };
+    if info.process_id != Some(pid) {
+        return false;
+    }
+    if matches!(
+        info.status,
+        CommandStatus::Running | CommandStatus::Healthy | CommandStatus::Unhealthy { .. }
+    ) && info.status != status
+    {
+        println!("Health: {} is now {:?}", id, status);
+        info.status = status;
+        events::emit_status(app, info);
+    }
+    true
+}
+
+fn probe_tcp(port: Option<u16>, timeout: Duration) -> Result<(), String> {
+    let port = port.ok_or_else(|| "TCP health check needs a port".to_string())?;
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&address, timeout)
+        .map(|_| ())
+        .map_err(|e| format!("Connect to {} failed: {}", address, e))
+}
+
+fn probe_http(url: Option<&str>, port: Option<u16>, timeout: Duration) -> Result<(), String> {
+    let url = match (url, port) {
+        (Some(url), _) => url.to_string(),
+        (None, Some(port)) => format!("http://127.0.0.1:{}/", port),
+        (None, None) => return Err("HTTP health check needs a url or a port".to_string()),
+    };
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.get(&url).send().map_err(|e| format!("GET {} failed: {}", url, e))?;
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        Err(format!("GET {} returned {}", url, status))
+    } else {
+        Ok(())
+    }
+}
+
+fn probe_mcp<R: Runtime>(app: &AppHandle<R>, id: &str, timeout: Duration) -> Result<(), String> {
+    let (channel, _) = mcp_channel(app, id, timeout)?;
+    channel.request("ping", json!({}), timeout).map(|_| ())
+}
@@ -0,0 +1,89 @@
+// Pieces shared by the runner's HTTP endpoints (the gateway and the per-server bridges)
+use std::io::Cursor;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Request, Response};
+
+// JSON-RPC error codes
+pub(crate) const PARSE_ERROR: i64 = -32700;
+pub(crate) const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+pub(crate) const INTERNAL_ERROR: i64 = -32603;
+
+pub(crate) type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+pub(crate) fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+pub(crate) fn header_value<'a>(request: &'a Request, field: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(field))
+        .map(|header| header.value.as_str())
+}
+
+// Query parameter from the request URL, undecoded (only used for ids we generated)
+pub(crate) fn query_param<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    let (_, query) = request.url().split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+pub(crate) fn path(request: &Request) -> &str {
+    request.url().split('?').next().unwrap_or_default()
+}
+
+// Browsers can reach loopback too, so requests from pages anywhere else are refused (DNS rebinding)
+pub(crate) fn origin_allowed(request: &Request) -> bool {
+    let Some(origin) = header_value(request, "Origin") else {
+        return true;
+    };
+    let host = origin.split("://").nth(1).unwrap_or(origin);
+    let host = host.split(['/', ':']).next().unwrap_or(host);
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]" | "tauri.localhost")
+}
+
+pub(crate) fn text_response(status: u16, text: &str) -> HttpResponse {
+    Response::from_string(text).with_status_code(status)
+}
+
+pub(crate) fn json_response(status: u16, body: &Value) -> HttpResponse {
+    Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+}
+
+pub(crate) fn accepted() -> HttpResponse {
+    Response::from_data(Vec::new()).with_status_code(202)
+}
+
+pub(crate) fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+// Reads and parses the request body, or the 400 response to send instead
+pub(crate) fn read_json(request: &mut Request) -> Result<Value, HttpResponse> {
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().read_to_string(&mut body) {
+        return Err(json_response(400, &error_response(Value::Null, PARSE_ERROR, &e.to_string())));
+    }
+    serde_json::from_str(&body)
+        .map_err(|e| json_response(400, &error_response(Value::Null, PARSE_ERROR, &e.to_string())))
+}
+
+// Runs `handle` over a single message or a batch; None when nothing needs a reply
+pub(crate) fn handle_batch(message: Value, mut handle: impl FnMut(Value) -> Option<Value>) -> Option<Value> {
+    match message {
+        Value::Array(batch) => {
+            let replies: Vec<Value> = batch.into_iter().filter_map(&mut handle).collect();
+            (!replies.is_empty()).then_some(Value::Array(replies))
+        }
+        message => handle(message),
+    }
+}
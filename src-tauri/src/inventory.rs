@@ -0,0 +1,257 @@
+// What a running server offers: tools, resources, resource templates and prompts.
+// Lists are fetched on demand, cached on the server's channel and refetched when the
+// server says one has changed.
+use std::sync::Arc;
+use std::thread;
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+
+use crate::events;
+use crate::mcp::{self, ServerSession};
+use crate::rpc::RpcChannel;
+
+// Guards against servers that keep handing out cursors
+const MAX_PAGES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ListKind {
+    Tools,
+    Resources,
+    ResourceTemplates,
+    Prompts,
+}
+
+impl ListKind {
+    fn method(self) -> &'static str {
+        match self {
+            ListKind::Tools => "tools/list",
+            ListKind::Resources => "resources/list",
+            ListKind::ResourceTemplates => "resources/templates/list",
+            ListKind::Prompts => "prompts/list",
+        }
+    }
+
+    // Field of the list result holding the page's items
+    fn field(self) -> &'static str {
+        match self {
+            ListKind::Tools => "tools",
+            ListKind::Resources => "resources",
+            ListKind::ResourceTemplates => "resourceTemplates",
+            ListKind::Prompts => "prompts",
+        }
+    }
+
+    // Server capability that has to be advertised for the list to exist
+    fn capability(self) -> &'static str {
+        match self {
+            ListKind::Tools => "tools",
+            ListKind::Resources | ListKind::ResourceTemplates => "resources",
+            ListKind::Prompts => "prompts",
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Tool {
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(rename = "inputSchema")]
+    pub(crate) input_schema: Value,
+    #[serde(rename = "outputSchema", default, skip_serializing_if = "Option::is_none")]
+    output_schema: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    annotations: Option<Value>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Resource {
+    pub(crate) uri: String,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    uri_template: String,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct PromptArgument {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default)]
+    required: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Prompt {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    arguments: Vec<PromptArgument>,
+}
+
+// Cached lists for one server process; None until first fetched or after a change notice
+#[derive(Default)]
+pub(crate) struct Inventory {
+    tools: Option<Vec<Tool>>,
+    resources: Option<Vec<Resource>>,
+    resource_templates: Option<Vec<ResourceTemplate>>,
+    prompts: Option<Vec<Prompt>>,
+}
+
+impl Inventory {
+    // Drops the cached list; returns whether there was one
+    fn invalidate(&mut self, kind: ListKind) -> bool {
+        match kind {
+            ListKind::Tools => self.tools.take().is_some(),
+            ListKind::Resources => self.resources.take().is_some(),
+            ListKind::ResourceTemplates => self.resource_templates.take().is_some(),
+            ListKind::Prompts => self.prompts.take().is_some(),
+        }
+    }
+}
+
+pub(crate) fn tools(channel: &RpcChannel, session: &ServerSession, refresh: bool) -> Result<Vec<Tool>, String> {
+    cached(channel, session, ListKind::Tools, refresh, |inventory| &mut inventory.tools)
+}
+
+pub(crate) fn resources(channel: &RpcChannel, session: &ServerSession, refresh: bool) -> Result<Vec<Resource>, String> {
+    cached(channel, session, ListKind::Resources, refresh, |inventory| &mut inventory.resources)
+}
+
+pub(crate) fn resource_templates(
+    channel: &RpcChannel,
+    session: &ServerSession,
+    refresh: bool,
+) -> Result<Vec<ResourceTemplate>, String> {
+    cached(channel, session, ListKind::ResourceTemplates, refresh, |inventory| {
+        &mut inventory.resource_templates
+    })
+}
+
+pub(crate) fn prompts(channel: &RpcChannel, session: &ServerSession, refresh: bool) -> Result<Vec<Prompt>, String> {
+    cached(channel, session, ListKind::Prompts, refresh, |inventory| &mut inventory.prompts)
+}
+
+fn cached<T: Clone + DeserializeOwned>(
+    channel: &RpcChannel,
+    session: &ServerSession,
+    kind: ListKind,
+    refresh: bool,
+    slot: fn(&mut Inventory) -> &mut Option<Vec<T>>,
+) -> Result<Vec<T>, String> {
+    if !session.supports(kind.capability()) {
+        return Err(format!("Server does not offer {}", kind.capability()));
+    }
+    if !refresh {
+        let mut inventory = channel.inventory.lock().map_err(|e| e.to_string())?;
+        if let Some(items) = slot(&mut inventory) {
+            return Ok(items.clone());
+        }
+    }
+
+    // Not holding the lock while waiting: the stdout thread may need it for a change notice
+    // before it gets to our response
+    let items: Vec<T> = list_all(channel, kind)?;
+    let mut inventory = channel.inventory.lock().map_err(|e| e.to_string())?;
+    *slot(&mut inventory) = Some(items.clone());
+    Ok(items)
+}
+
+// Follows nextCursor until the server has nothing more to give
+fn list_all<T: DeserializeOwned>(channel: &RpcChannel, kind: ListKind) -> Result<Vec<T>, String> {
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let mut result = channel.request(kind.method(), params, mcp::REQUEST_TIMEOUT)?;
+        let page = result.get_mut(kind.field()).map(Value::take).unwrap_or_else(|| json!([]));
+        let page: Vec<T> =
+            serde_json::from_value(page).map_err(|e| format!("Invalid '{}' result: {}", kind.method(), e))?;
+        items.extend(page);
+
+        cursor = result.get("nextCursor").and_then(Value::as_str).map(str::to_string);
+        if cursor.is_none() {
+            return Ok(items);
+        }
+    }
+    Err(format!("'{}' returned more than {} pages", kind.method(), MAX_PAGES))
+}
+
+// Handles `notifications/*/list_changed` from the server; returns false for any other notification.
+// A list that was cached is refetched in the background and the frontend told once it's fresh.
+pub(crate) fn handle_notification<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    channel: &Arc<RpcChannel>,
+    method: &str,
+) -> bool {
+    let kinds: &[ListKind] = match method {
+        "notifications/tools/list_changed" => &[ListKind::Tools],
+        // Templates change along with resources; there's no separate notice for them
+        "notifications/resources/list_changed" => &[ListKind::Resources, ListKind::ResourceTemplates],
+        "notifications/prompts/list_changed" => &[ListKind::Prompts],
+        _ => return false,
+    };
+
+    for &kind in kinds {
+        let was_cached = match channel.inventory.lock() {
+            Ok(mut inventory) => inventory.invalidate(kind),
+            Err(_) => false,
+        };
+        if !was_cached {
+            events::emit_inventory(app, id, kind);
+            continue;
+        }
+
+        let app = app.clone();
+        let id = id.to_string();
+        let channel = Arc::clone(channel);
+        thread::spawn(move || {
+            let session = channel.session.lock().ok().and_then(|session| session.clone());
+            let Some(session) = session else {
+                return;
+            };
+            let result = match kind {
+                ListKind::Tools => tools(&channel, &session, true).map(|_| ()),
+                ListKind::Resources => resources(&channel, &session, true).map(|_| ()),
+                ListKind::ResourceTemplates => resource_templates(&channel, &session, true).map(|_| ()),
+                ListKind::Prompts => prompts(&channel, &session, true).map(|_| ()),
+            };
+            if let Err(e) = result {
+                eprintln!("Inventory: Failed to refresh {:?} for {}: {}", kind, id, e);
+            }
+            events::emit_inventory(&app, &id, kind);
+        });
+    }
+    true
+}
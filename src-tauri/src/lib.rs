@@ -1,12 +1,13 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::thread;
+use std::time::{Duration, Instant};
 // Add nix imports for signals
 #[cfg(unix)]
 use nix::sys::signal::{self, Signal};
@@ -15,50 +16,328 @@ use nix::unistd::Pid;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::TrayIconBuilder,
-    Manager, Runtime, State,
+    AppHandle, Manager, Runtime, State,
 };
 use reqwest::header::{HeaderMap, AUTHORIZATION};
 use serde_json::Value;
 
+mod bridge;
+mod clients;
+mod config_file;
+mod config_schema;
+mod config_watch;
+mod dependencies;
+mod error_detection;
+mod events;
+mod gateway;
+mod health;
+mod http;
+mod inventory;
+mod logs;
+mod mcp;
+mod output;
+mod remote;
+mod rpc;
+mod schema;
+mod tool_call;
+mod traffic;
+
+// Upper bound for the delay between automatic restarts
+const MAX_RESTART_BACKOFF_MS: u64 = 60_000;
+// A process that stays up this long is considered stable and its restart count starts over
+const RESTART_RESET_AFTER: Duration = Duration::from_secs(60);
+// How long servers get to exit gracefully when the app quits
+const QUIT_GRACE_PERIOD: Duration = Duration::from_secs(2);
+// How long a grouped start waits for a dependency to come up
+const DEPENDENCY_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+// What to do when a server's process exits without being asked to
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum RestartPolicy {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+impl RestartPolicy {
+    fn is_default(&self) -> bool {
+        *self == RestartPolicy::default()
+    }
+
+    fn should_restart(self, success: bool) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => !success,
+            RestartPolicy::Always => true,
+        }
+    }
+}
+
+fn default_max_restarts() -> u32 {
+    5
+}
+
+fn default_restart_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_stop_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_inherit_env() -> bool {
+    true
+}
+
+fn default_output_max_lines() -> usize {
+    5000
+}
+
+fn default_output_max_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_persist_logs() -> bool {
+    true
+}
+
+fn default_log_file_max_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
+fn default_log_file_retain() -> usize {
+    5
+}
+
+// Fields equal to their default are left out when the config is written, so the file keeps
+// only what the user set and later changes to a default still reach existing configs
+fn is_default_max_restarts(value: &u32) -> bool {
+    *value == default_max_restarts()
+}
+
+fn is_default_restart_backoff_ms(value: &u64) -> bool {
+    *value == default_restart_backoff_ms()
+}
+
+fn is_default_stop_timeout_ms(value: &u64) -> bool {
+    *value == default_stop_timeout_ms()
+}
+
+fn is_default_inherit_env(value: &bool) -> bool {
+    *value == default_inherit_env()
+}
+
+fn is_default_output_max_lines(value: &usize) -> bool {
+    *value == default_output_max_lines()
+}
+
+fn is_default_output_max_bytes(value: &usize) -> bool {
+    *value == default_output_max_bytes()
+}
+
+fn is_default_persist_logs(value: &bool) -> bool {
+    *value == default_persist_logs()
+}
+
+fn is_default_log_file_max_bytes(value: &u64) -> bool {
+    *value == default_log_file_max_bytes()
+}
+
+fn is_default_log_file_retain(value: &usize) -> bool {
+    *value == default_log_file_retain()
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 struct MCPServerConfig {
+    // Empty for remote servers
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     command: String,
+    #[serde(default)]
     args: Vec<String>,
+    // How the runner reaches the server; inferred from `url` when omitted
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    transport: Option<remote::Transport>,
+    // Endpoint of a remote (sse / streamable-http) server, and headers sent with every request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    headers: HashMap<String, String>,
     #[serde(default)]
     env: std::collections::HashMap<String, String>,
     port: Option<u16>,
+    #[serde(default, skip_serializing_if = "RestartPolicy::is_default")]
+    restart: RestartPolicy,
+    #[serde(rename = "maxRestarts", default = "default_max_restarts", skip_serializing_if = "is_default_max_restarts")]
+    max_restarts: u32,
+    #[serde(
+        rename = "restartBackoffMs",
+        default = "default_restart_backoff_ms",
+        skip_serializing_if = "is_default_restart_backoff_ms"
+    )]
+    restart_backoff_ms: u64,
+    // Start this server in the background when the app launches
+    #[serde(rename = "autoStart", default, skip_serializing_if = "std::ops::Not::not")]
+    auto_start: bool,
+    // Servers that must be up before this one starts in a group
+    #[serde(rename = "dependsOn", default, skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<String>,
+    #[serde(rename = "healthCheck", default, skip_serializing_if = "Option::is_none")]
+    health_check: Option<health::HealthCheckConfig>,
+    // How stderr lines are classified as errors; log level parsing is on when omitted
+    #[serde(rename = "errorDetection", default, skip_serializing_if = "Option::is_none")]
+    error_detection: Option<error_detection::ErrorDetectionConfig>,
+    // Working directory for the process; defaults to the runner's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
+    // Whether the process sees the runner's environment (filtered by the lists below)
+    #[serde(rename = "inheritEnv", default = "default_inherit_env", skip_serializing_if = "is_default_inherit_env")]
+    inherit_env: bool,
+    #[serde(rename = "envAllowlist", default, skip_serializing_if = "Vec::is_empty")]
+    env_allowlist: Vec<String>,
+    #[serde(rename = "envDenylist", default, skip_serializing_if = "Vec::is_empty")]
+    env_denylist: Vec<String>,
+    // Caps on the output kept in memory; the oldest lines go first
+    #[serde(
+        rename = "outputMaxLines",
+        default = "default_output_max_lines",
+        skip_serializing_if = "is_default_output_max_lines"
+    )]
+    output_max_lines: usize,
+    #[serde(
+        rename = "outputMaxBytes",
+        default = "default_output_max_bytes",
+        skip_serializing_if = "is_default_output_max_bytes"
+    )]
+    output_max_bytes: usize,
+    // Also write output to <app data>/logs/<id>.log, rotated once it reaches logFileMaxBytes
+    #[serde(rename = "persistLogs", default = "default_persist_logs", skip_serializing_if = "is_default_persist_logs")]
+    persist_logs: bool,
+    #[serde(
+        rename = "logFileMaxBytes",
+        default = "default_log_file_max_bytes",
+        skip_serializing_if = "is_default_log_file_max_bytes"
+    )]
+    log_file_max_bytes: u64,
+    // Rotated files to keep besides the live one
+    #[serde(
+        rename = "logFileRetain",
+        default = "default_log_file_retain",
+        skip_serializing_if = "is_default_log_file_retain"
+    )]
+    log_file_retain: usize,
+    // Run `command` and `args` through sh -c / cmd /C instead of executing them directly
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    shell: bool,
+    // How long a graceful stop may take before the process is killed
+    #[serde(
+        rename = "stopTimeoutMs",
+        default = "default_stop_timeout_ms",
+        skip_serializing_if = "is_default_stop_timeout_ms"
+    )]
+    stop_timeout_ms: u64,
+    // Serve this stdio server over Streamable HTTP and SSE on `port` while it runs
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    bridge: bool,
+    // Record all JSON-RPC traffic with the server from the moment it starts
+    #[serde(rename = "captureTraffic", default, skip_serializing_if = "std::ops::Not::not")]
+    capture_traffic: bool,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
-struct Config {
-    #[serde(rename = "mcpServers")]
-    mcp_servers: HashMap<String, MCPServerConfig>,
+impl MCPServerConfig {
+    // Entries with a `url` and no `type` are Streamable HTTP, like other clients assume
+    fn transport(&self) -> remote::Transport {
+        match (self.transport, &self.url) {
+            (Some(transport), _) => transport,
+            (None, Some(_)) => remote::Transport::StreamableHttp,
+            (None, None) => remote::Transport::Stdio,
+        }
+    }
+
+    // What's missing for the server to be startable at all
+    fn validate(&self) -> Result<(), String> {
+        match self.transport() {
+            remote::Transport::Stdio if self.command.trim().is_empty() => Err("'command' is required".to_string()),
+            remote::Transport::Sse | remote::Transport::StreamableHttp if self.url.is_none() => {
+                Err("'url' is required for remote servers".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
-impl Default for Config {
+impl Default for MCPServerConfig {
     fn default() -> Self {
-        Config {
-            mcp_servers: HashMap::new(),
+        MCPServerConfig {
+            command: String::new(),
+            transport: None,
+            url: None,
+            headers: HashMap::new(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            port: None,
+            restart: RestartPolicy::default(),
+            max_restarts: default_max_restarts(),
+            restart_backoff_ms: default_restart_backoff_ms(),
+            auto_start: false,
+            depends_on: Vec::new(),
+            health_check: None,
+            error_detection: None,
+            cwd: None,
+            inherit_env: default_inherit_env(),
+            env_allowlist: Vec::new(),
+            env_denylist: Vec::new(),
+            output_max_lines: default_output_max_lines(),
+            output_max_bytes: default_output_max_bytes(),
+            persist_logs: default_persist_logs(),
+            log_file_max_bytes: default_log_file_max_bytes(),
+            log_file_retain: default_log_file_retain(),
+            shell: false,
+            bridge: false,
+            capture_traffic: false,
+            stop_timeout_ms: default_stop_timeout_ms(),
         }
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+struct Config {
+    // Kept so editors can keep validating the file against the published schema
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    schema: Option<String>,
+    #[serde(rename = "mcpServers")]
+    mcp_servers: HashMap<String, MCPServerConfig>,
+    // The aggregating endpoint in front of all running servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gateway: Option<gateway::GatewayConfig>,
+    // Picking up external edits to the config file; on when omitted
+    #[serde(rename = "hotReload", default, skip_serializing_if = "Option::is_none")]
+    hot_reload: Option<config_watch::HotReloadConfig>,
+}
+
+// Running processes keyed by server id; the child slot is emptied once the monitor thread takes it
+type ProcessMap = HashMap<String, (Arc<Mutex<Option<Child>>>, CommandInfo)>;
+
 // Store for running processes and their info
 struct ProcessStore {
-    inner: Arc<Mutex<HashMap<String, (Arc<Mutex<Option<Child>>>, CommandInfo)>>>,
+    inner: Arc<Mutex<ProcessMap>>,
+    // Set (with `inner` locked) once the app starts reaping on quit; nothing may spawn after that
+    quitting: AtomicBool,
 }
 
 impl ProcessStore {
     fn new() -> Self {
         ProcessStore {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            quitting: AtomicBool::new(false),
         }
     }
 }
 
 // Store for process output
 struct OutputStore {
-    inner: Arc<Mutex<HashMap<String, Vec<String>>>>,
+    inner: Arc<Mutex<HashMap<String, output::OutputBuffer>>>,
 }
 
 impl OutputStore {
@@ -69,9 +348,49 @@ impl OutputStore {
     }
 }
 
+// Store for the on-disk log file of each server that persists its output
+struct LogStore {
+    inner: Arc<Mutex<HashMap<String, logs::LogFile>>>,
+}
+
+impl LogStore {
+    fn new() -> Self {
+        LogStore {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+// Store for the JSON-RPC channel to each server's stdio
+struct ChannelStore {
+    inner: Arc<Mutex<HashMap<String, Arc<rpc::RpcChannel>>>>,
+}
+
+impl ChannelStore {
+    fn new() -> Self {
+        ChannelStore {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
 // Store for configuration
 struct ConfigStore(Mutex<Config>);
 
+// What brought a process to its end, reported in CommandStatus::Finished
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EndedBy {
+    // The process exited on its own
+    Exit,
+    // Exited after a graceful stop (SIGTERM)
+    Stop,
+    // Ignored the graceful stop and was killed when the stop timeout ran out
+    StopTimeout,
+    // Killed through force_kill_command
+    ForceKill,
+}
+
 // Define Command Status enum
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 #[serde(tag = "state", content = "data")]
@@ -79,11 +398,27 @@ pub enum CommandStatus {
     Idle,
     Starting,
     Running,
+    // Running and passing its health check
+    Healthy,
+    // Running but failing its health check
+    Unhealthy {
+        failures: u32,
+        message: String,
+    },
     Stopping,
     Killing,
+    Restarting {
+        attempt: u32,
+        delay_ms: u64,
+    },
+    CrashLoop {
+        restarts: u32,
+        code: Option<i32>,
+    },
     Finished {
         code: Option<i32>,
         success: bool,
+        ended_by: EndedBy,
     },
     Error {
         message: String,
@@ -97,9 +432,68 @@ struct CommandInfo {
     status: CommandStatus,
     // Keep these for backwards compatibility during transition
     is_running: bool,
+    // Set when the process fails to start or exits unsuccessfully
     has_error: bool,
+    // Stderr lines flagged by error detection since the process started, and the latest one
+    error_lines: u32,
+    last_error: Option<String>,
     process_id: Option<u32>,
     port: Option<u16>,
+    // Automatic restarts since the last manual start
+    restart_count: u32,
+    // Set by stop/force kill so the monitor knows the exit was requested, and how
+    #[serde(skip)]
+    stop_request: Option<EndedBy>,
+}
+
+// Loads the default config into the store at launch, starts the gateway if enabled and every
+// server marked `autoStart`, along with whatever they depend on
+fn autostart_servers<R: Runtime>(app: &AppHandle<R>) {
+    let config = match config_file::read_default(app) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Autostart: {}", e);
+            return;
+        }
+    };
+
+    match app.state::<ConfigStore>().0.lock() {
+        Ok(mut store) => *store = config.clone(),
+        Err(e) => {
+            eprintln!("Autostart: Failed to lock config store: {}", e);
+            return;
+        }
+    }
+    if let Ok(path) = config_file::default_path(app) {
+        config_watch::watch(app, path);
+    }
+
+    if let Some(gateway_config) = config.gateway.as_ref().filter(|gateway_config| gateway_config.enabled) {
+        if let Err(e) = gateway::start(app, gateway_config.port) {
+            eprintln!("Autostart: {}", e);
+        }
+    }
+
+    let mut ids: Vec<String> = config
+        .mcp_servers
+        .iter()
+        .filter(|(_, server)| server.auto_start)
+        .map(|(id, _)| id.clone())
+        .collect();
+    if ids.is_empty() {
+        return;
+    }
+    ids.sort();
+
+    println!("Autostart: Starting {}", ids.join(", "));
+    match start_servers_in_order(app, &ids) {
+        Ok((_, errors)) => {
+            for e in errors {
+                eprintln!("Autostart: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Autostart: {}", e),
+    }
 }
 
 #[tauri::command]
@@ -108,64 +502,146 @@ async fn load_config<R: Runtime>(
     config_store: State<'_, ConfigStore>,
     app: tauri::AppHandle<R>,
 ) -> Result<Config, String> {
-    let config = if let Some(path) = config_path {
-        let content =
-            fs::read_to_string(path).map_err(|e| format!("Failed to read config file: {}", e))?;
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse config file: {}", e))?
-    } else {
-        // Try to load from default location
-        let app_dir = app
-            .path()
-            .app_config_dir()
-            .map_err(|_| "Failed to get app config directory".to_string())?;
-        let config_path = app_dir.join("mcp-config.json");
-
-        if config_path.exists() {
-            let content = fs::read_to_string(config_path)
-                .map_err(|e| format!("Failed to read config file: {}", e))?;
-            serde_json::from_str(&content)
-                .map_err(|e| format!("Failed to parse config file: {}", e))?
-        } else {
-            Config::default()
+    let (path, config) = match config_path {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            let config = config_file::read(&path)?;
+            (path, config)
         }
+        None => (config_file::default_path(&app)?, config_file::read_default(&app)?),
     };
 
     let mut store = config_store.0.lock().map_err(|e| e.to_string())?;
     *store = config.clone();
+    config_watch::watch(&app, path);
     Ok(config)
 }
 
+// Checks everything save_config would refuse to persist
+fn validate_config(config: &Config) -> Result<(), String> {
+    let value = serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    config_schema::parse(value).map(|_| ())
+}
+
+// Every problem with config text, each located by JSON pointer, for the editor to highlight
+#[tauri::command]
+async fn validate_config_text(content: String) -> Result<Vec<schema::SchemaError>, String> {
+    Ok(config_schema::check_text(&content))
+}
+
+// The JSON Schema the config file is validated against
+#[tauri::command]
+async fn get_config_schema() -> Result<Value, String> {
+    Ok(config_schema::CONFIG_SCHEMA.clone())
+}
+
+// The config as the app currently has it, including external edits picked up since loading
+#[tauri::command]
+async fn get_config(config_store: State<'_, ConfigStore>) -> Result<Config, String> {
+    Ok(config_store.0.lock().map_err(|e| e.to_string())?.clone())
+}
+
 #[tauri::command]
 async fn save_config<R: Runtime>(
-    config: Config,
+    config: Value,
     config_path: Option<String>,
     config_store: State<'_, ConfigStore>,
     app: tauri::AppHandle<R>,
 ) -> Result<(), String> {
+    // Checked as sent, so unknown keys are reported instead of silently dropped
+    let config = config_schema::parse(config)?;
+
     // Update the config store with the new configuration
     {
         let mut store = config_store.0.lock().map_err(|e| e.to_string())?;
         *store = config.clone();
     }
 
-    let config_path = if let Some(path) = config_path {
-        PathBuf::from(path)
-    } else {
-        let app_dir = app
-            .path()
-            .app_config_dir()
-            .map_err(|_| "Failed to get app config directory".to_string())?;
-        fs::create_dir_all(&app_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-        app_dir.join("mcp-config.json")
+    let config_path = match config_path {
+        Some(path) => PathBuf::from(path),
+        None => config_file::default_path(&app)?,
     };
+    config_file::write(&config_path, &config)?;
+    config_watch::watch(&app, config_path);
+    Ok(())
+}
 
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+// Servers defined by other MCP clients, with conflicts against the current config marked
+#[tauri::command]
+async fn preview_import<R: Runtime>(
+    client: Option<clients::Client>,
+    path: Option<String>,
+    config_store: State<'_, ConfigStore>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<clients::ImportSource>, String> {
+    let existing = config_store.0.lock().map_err(|e| e.to_string())?.clone();
+    clients::preview(&app, client, path.as_deref(), &existing)
+}
 
-    fs::write(config_path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
+// Merges the chosen servers from a client's config file into ours and saves it
+#[tauri::command]
+async fn import_servers<R: Runtime>(
+    client: clients::Client,
+    path: Option<String>,
+    names: Vec<String>,
+    on_conflict: Option<clients::OnConflict>,
+    config_store: State<'_, ConfigStore>,
+    app: tauri::AppHandle<R>,
+) -> Result<clients::ImportOutcome, String> {
+    let mut store = config_store.0.lock().map_err(|e| e.to_string())?;
+    let mut config = store.clone();
+    let outcome = clients::merge(&app, &mut config, client, path.as_deref(), &names, on_conflict.unwrap_or_default())?;
+    validate_config(&config)?;
 
-    Ok(())
+    config_file::write_default(&app, &config)?;
+    *store = config;
+    Ok(outcome)
+}
+
+// Writes the chosen servers, or a single entry for the gateway, into a client's config file
+#[tauri::command]
+async fn sync_to_client<R: Runtime>(
+    client: clients::Client,
+    server_ids: Vec<String>,
+    gateway: Option<bool>,
+    path: Option<String>,
+    dry_run: Option<bool>,
+    config_store: State<'_, ConfigStore>,
+    app: tauri::AppHandle<R>,
+) -> Result<clients::SyncReport, String> {
+    let config = config_store.0.lock().map_err(|e| e.to_string())?.clone();
+
+    let mut entries = Vec::new();
+    if gateway.unwrap_or(false) {
+        // The running gateway's port wins over the configured one
+        let port = match gateway::status(&app)?.port {
+            Some(port) => port,
+            None => config.gateway.as_ref().map_or_else(gateway::default_port, |gateway| gateway.port),
+        };
+        let url = gateway::endpoint_url(port);
+        entries.push((clients::GATEWAY_ENTRY.to_string(), clients::gateway_entry(client, &url)));
+    }
+    for id in &server_ids {
+        let server = config
+            .mcp_servers
+            .get(id)
+            .ok_or_else(|| format!("Server '{}' not found", id))?;
+        let entry = clients::export_entry(client, server).map_err(|e| format!("Server '{}': {}", id, e))?;
+        entries.push((id.clone(), entry));
+    }
+    if entries.is_empty() {
+        return Err("Nothing to sync".to_string());
+    }
+
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => client.config_path(&app)?,
+    };
+    let report = clients::sync(client, &path, entries, dry_run.unwrap_or(false))?;
+    if report.written {
+        println!("Sync: Updated {}", path.display());
+    }
+    Ok(report)
 }
 
 #[tauri::command]
@@ -180,39 +656,72 @@ async fn add_server<R: Runtime>(
 ) -> Result<Config, String> {
     let mut store = config_store.0.lock().map_err(|e| e.to_string())?;
 
-    store.mcp_servers.insert(
-        name,
+    let mut config = store.clone();
+    config.mcp_servers.insert(
+        name.clone(),
         MCPServerConfig {
             command,
             args,
             env: env.unwrap_or_default(),
             port,
+            ..Default::default()
         },
     );
+    validate_config(&config).map_err(|e| format!("Cannot add '{}': {}", name, e))?;
 
-    // Ensure config directory exists and save the updated config
-    let app_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|_| "Failed to get app config directory".to_string())?;
-    fs::create_dir_all(&app_dir)
-        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    config_file::write_default(&app, &config)?;
+    *store = config;
+    Ok(store.clone())
+}
 
-    let config_path = app_dir.join("mcp-config.json");
-    let content = serde_json::to_string_pretty(&*store)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+// Edits the fields the server form shows, keeping the rest of the entry. A rename carries over
+// to the `dependsOn` lists that name the server.
+#[tauri::command]
+async fn update_server<R: Runtime>(
+    original_name: String,
+    name: String,
+    command: String,
+    args: Vec<String>,
+    env: Option<HashMap<String, String>>,
+    port: Option<u16>,
+    app: tauri::AppHandle<R>,
+) -> Result<Config, String> {
+    let config_store = app.state::<ConfigStore>();
+    let mut store = config_store.0.lock().map_err(|e| e.to_string())?;
 
-    fs::write(config_path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
+    let mut config = store.clone();
+    let mut server = config
+        .mcp_servers
+        .remove(&original_name)
+        .ok_or_else(|| "Server not found".to_string())?;
+    if name != original_name {
+        if config.mcp_servers.contains_key(&name) {
+            return Err(format!("Server '{}' already exists", name));
+        }
+        for other in config.mcp_servers.values_mut() {
+            for dependency in other.depends_on.iter_mut().filter(|dependency| **dependency == original_name) {
+                *dependency = name.clone();
+            }
+        }
+    }
+    server.command = command;
+    server.args = args;
+    server.env = env.unwrap_or_default();
+    server.port = port;
+    config.mcp_servers.insert(name.clone(), server);
+    validate_config(&config).map_err(|e| format!("Cannot update '{}': {}", name, e))?;
 
+    config_file::write_default(&app, &config)?;
+    *store = config;
     Ok(store.clone())
 }
 
 #[tauri::command]
-async fn start_command(
+async fn start_command<R: Runtime>(
     id: String,
     process_store: State<'_, ProcessStore>,
     config_store: State<'_, ConfigStore>,
-    output_store: State<'_, OutputStore>,
+    app: tauri::AppHandle<R>,
 ) -> Result<CommandInfo, String> {
     // --- Check Preconditions (Locks held briefly) ---
     {
@@ -234,11 +743,364 @@ async fn start_command(
              .ok_or_else(|| format!("Server '{}' not found in configuration", id))?
     }; // config_store lock released
 
+    // Remote servers connect with a blocking HTTP client, which must stay off the async runtime
+    tauri::async_runtime::spawn_blocking(move || spawn_server(&app, &id, server_config, 0))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Delay before restart `attempt` (1-based): the base backoff doubled per attempt, capped
+fn restart_delay_ms(base_ms: u64, attempt: u32) -> u64 {
+    let factor = 1u64 << attempt.saturating_sub(1).min(20);
+    base_ms.saturating_mul(factor).min(MAX_RESTART_BACKOFF_MS)
+}
+
+// Builds the Command for a server plus a printable form of it for logs and errors.
+// Args are passed through verbatim unless the server opts into `shell`, in which case
+// the command line is joined and handed to sh/cmd as before.
+fn build_command(server_config: &MCPServerConfig) -> (std::process::Command, String) {
+    if server_config.shell {
+        let (shell, shell_arg) = if cfg!(target_os = "windows") {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let full_command = format!("{} {}", server_config.command, server_config.args.join(" "));
+        let mut command = std::process::Command::new(shell);
+        command.arg(shell_arg).arg(&full_command);
+        (command, full_command)
+    } else {
+        let full_command = format!("{} {:?}", server_config.command, server_config.args);
+        let mut command = std::process::Command::new(resolve_program(&server_config.command));
+        command.args(&server_config.args);
+        (command, full_command)
+    }
+}
+
+// Variables a process generally can't do without, kept even when inheritance is restricted
+const ESSENTIAL_ENV_VARS: &[&str] = &[
+    "PATH", "HOME", "USER", "LOGNAME", "SHELL", "LANG", "TMPDIR", "TEMP", "TMP",
+    "SYSTEMROOT", "SYSTEMDRIVE", "USERPROFILE", "APPDATA", "LOCALAPPDATA", "PATHEXT", "COMSPEC",
+];
+
+// Matches an env var name against an allowlist/denylist entry; a trailing `*` matches any suffix
+fn env_pattern_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+// Decides which of the runner's own variables a server inherits, then layers its `env` on top.
+// Inheritance is restricted to the essentials plus `envAllowlist` when `inheritEnv` is off or an
+// allowlist is given; `envDenylist` is applied last and wins over both.
+fn apply_environment(command: &mut std::process::Command, server_config: &MCPServerConfig) {
+    let restricted = !server_config.inherit_env || !server_config.env_allowlist.is_empty();
+    if restricted || !server_config.env_denylist.is_empty() {
+        let inherited: Vec<(String, String)> = std::env::vars()
+            .filter(|(name, _)| {
+                !restricted
+                    || ESSENTIAL_ENV_VARS.iter().any(|essential| essential.eq_ignore_ascii_case(name))
+                    || server_config.env_allowlist.iter().any(|pattern| env_pattern_matches(pattern, name))
+            })
+            .filter(|(name, _)| !server_config.env_denylist.iter().any(|pattern| env_pattern_matches(pattern, name)))
+            .collect();
+        command.env_clear();
+        command.envs(inherited);
+    }
+    if !server_config.env.is_empty() {
+        command.envs(&server_config.env);
+    }
+}
+
+// std only finds `.exe` files on PATH on Windows, but launchers like npx and uvx ship as `.cmd`
+#[cfg(windows)]
+fn resolve_program(program: &str) -> PathBuf {
+    let path = PathBuf::from(program);
+    if path.extension().is_some() || path.components().count() > 1 {
+        return path;
+    }
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let Some(search_path) = std::env::var_os("PATH") else {
+        return path;
+    };
+    for dir in std::env::split_paths(&search_path) {
+        for ext in extensions.split(';').filter(|ext| !ext.is_empty()) {
+            let candidate = dir.join(format!("{}{}", program, ext));
+            if candidate.is_file() {
+                return candidate;
+            }
+        }
+    }
+    path
+}
+
+#[cfg(not(windows))]
+fn resolve_program(program: &str) -> PathBuf {
+    PathBuf::from(program)
+}
+
+// (Re)opens the log file for `id` on a manual start, or drops it if the server doesn't persist logs
+fn open_log_file<R: Runtime>(app: &AppHandle<R>, id: &str, server_config: &MCPServerConfig) {
+    let log_file = if server_config.persist_logs {
+        logs::log_dir(app).and_then(|dir| {
+            logs::LogFile::open(&dir, id, server_config.log_file_max_bytes, server_config.log_file_retain)
+        })
+    } else {
+        Err(String::new())
+    };
+
+    let log_store = app.state::<LogStore>();
+    let Ok(mut log_files) = log_store.inner.lock() else {
+        return;
+    };
+    match log_file {
+        Ok(log_file) => {
+            log_files.insert(id.to_string(), log_file);
+        }
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("Logs: {}", e);
+            }
+            log_files.remove(id);
+        }
+    }
+}
+
+// Starts, moves or stops the bridge for `id` to match its config
+fn sync_bridge<R: Runtime>(app: &AppHandle<R>, id: &str, server_config: &MCPServerConfig) {
+    let port = match (server_config.bridge, server_config.port) {
+        (true, Some(port)) => port,
+        (true, None) => {
+            append_output(app, id, output::Stream::System, "Bridge: no port configured".to_string());
+            bridge::stop(app, id);
+            return;
+        }
+        (false, _) => {
+            bridge::stop(app, id);
+            return;
+        }
+    };
+    let message = match bridge::start(app, id, port) {
+        Ok(status) => format!("Bridge: serving {} (SSE: {})", status.url, status.sse_url),
+        Err(e) => format!("Bridge: {}", e),
+    };
+    append_output(app, id, output::Stream::System, message);
+}
+
+// Appends a line to a server's output buffer and pushes it to the frontend
+fn append_output<R: Runtime>(app: &AppHandle<R>, id: &str, stream: output::Stream, line: String) {
+    append_line(app, id, stream, line, false);
+}
+
+fn append_line<R: Runtime>(app: &AppHandle<R>, id: &str, stream: output::Stream, line: String, error: bool) {
+    let output_store = app.state::<OutputStore>();
+    let Ok(mut store) = output_store.inner.lock() else {
+        eprintln!("Output: Failed to lock output store for {}", id);
+        return;
+    };
+    if let Some(buffer) = store.get_mut(id) {
+        let record = buffer.push(stream, line, error);
+        events::emit_output(app, id, buffer.session(), &record);
+
+        // Still under the output lock so the file gets records in sequence order
+        if let Ok(mut log_files) = app.state::<LogStore>().inner.lock() {
+            if let Some(log_file) = log_files.get_mut(id) {
+                if let Err(e) = log_file.write(&record) {
+                    eprintln!("Output: {}", e);
+                }
+            }
+        }
+    }
+}
+
+// Routes a message from the server: responses to the runner's own requests and notifications
+// it acts on aren't server output
+fn handle_server_line<R: Runtime>(app: &AppHandle<R>, id: &str, channel: Option<&Arc<rpc::RpcChannel>>, line: String) {
+    if let Some(channel) = channel {
+        match channel.handle_line(&line) {
+            rpc::Incoming::Handled => return,
+            rpc::Incoming::Notification { method, message } => {
+                bridge::broadcast(app, id, &message);
+                if inventory::handle_notification(app, id, channel, &method) {
+                    return;
+                }
+            }
+            rpc::Incoming::Output => {}
+        }
+    }
+    append_output(app, id, output::Stream::Stdout, line);
+}
+
+// Connects to a remote server in place of spawning a process. The entry goes Running once
+// the MCP handshake succeeds and Finished when the connection is gone.
+fn connect_remote<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    server_config: &MCPServerConfig,
+    transport: remote::Transport,
+    starting_info: CommandInfo,
+) -> Result<CommandInfo, String> {
+    let url = server_config.url.clone().unwrap_or_default();
+    append_output(app, id, output::Stream::System, format!("Remote: connecting to {} ({:?})", url, transport));
+
+    let (outbound, inbound) = match remote::connect(transport, &url, &server_config.headers) {
+        Ok(connection) => connection,
+        Err(e) => {
+            append_output(app, id, output::Stream::System, format!("Remote: {}", e));
+            return Ok(set_remote_status(app, id, None, CommandStatus::Error { message: e }).unwrap_or(starting_info));
+        }
+    };
+    let channel = Arc::new(rpc::RpcChannel::new(outbound));
+    if server_config.capture_traffic {
+        start_capture(app, id, &channel);
+    }
+    {
+        let channel_store = app.state::<ChannelStore>();
+        let mut channels = channel_store.inner.lock().map_err(|e| e.to_string())?;
+        channels.insert(id.to_string(), Arc::clone(&channel));
+    }
+
+    // Reader: the remote counterpart of the stdout and monitor threads
+    let app_reader = app.clone();
+    let id_reader = id.to_string();
+    let channel_reader = Arc::clone(&channel);
+    thread::spawn(move || {
+        for line in inbound {
+            // A stop or reconnect replaced this channel; what's still in flight belongs to no one
+            if !is_current_channel(&app_reader, &id_reader, &channel_reader) {
+                return;
+            }
+            handle_server_line(&app_reader, &id_reader, Some(&channel_reader), line);
+        }
+        channel_reader.close();
+        let status = CommandStatus::Finished { code: None, success: false, ended_by: EndedBy::Exit };
+        if set_remote_status(&app_reader, &id_reader, Some(&channel_reader), status).is_some() {
+            append_output(&app_reader, &id_reader, output::Stream::System, "Remote: connection closed".to_string());
+            bridge::stop(&app_reader, &id_reader);
+        }
+    });
+
+    // Connectivity is only proven once the server answers initialize
+    let app_handshake = app.clone();
+    let id_handshake = id.to_string();
+    thread::spawn(move || {
+        let status = match mcp_channel(&app_handshake, &id_handshake, mcp::REQUEST_TIMEOUT) {
+            Ok(_) => CommandStatus::Running,
+            Err(e) => {
+                append_output(&app_handshake, &id_handshake, output::Stream::System, format!("Remote: {}", e));
+                channel.close();
+                CommandStatus::Error { message: e }
+            }
+        };
+        set_remote_status(&app_handshake, &id_handshake, Some(&channel), status);
+    });
+
+    Ok(starting_info)
+}
+
+// Whether `channel` is still the one registered for `id`
+fn is_current_channel<R: Runtime>(app: &AppHandle<R>, id: &str, channel: &Arc<rpc::RpcChannel>) -> bool {
+    let channel_store = app.state::<ChannelStore>();
+    let Ok(channels) = channel_store.inner.lock() else {
+        return false;
+    };
+    channels.get(id).is_some_and(|current| Arc::ptr_eq(current, channel))
+}
+
+// Moves a remote entry to `status`, unless it was stopped or has since been reconnected through
+// another channel. Returns the updated info if it changed.
+fn set_remote_status<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    channel: Option<&Arc<rpc::RpcChannel>>,
+    status: CommandStatus,
+) -> Option<CommandInfo> {
+    if channel.is_some_and(|channel| !is_current_channel(app, id, channel)) {
+        return None;
+    }
+
+    let process_store = app.state::<ProcessStore>();
+    let mut store = process_store.inner.lock().ok()?;
+    let (_, info) = store.get_mut(id)?;
+    if info.stop_request.is_some() || !info.is_running {
+        return None;
+    }
+    let finished = !matches!(status, CommandStatus::Running);
+    info.is_running = !finished;
+    info.has_error = finished;
+    info.status = status;
+    events::emit_status(app, info);
+    Some(info.clone())
+}
+
+// Begins a new capture file for the channel, noting where in the server's output
+fn start_capture<R: Runtime>(app: &AppHandle<R>, id: &str, channel: &rpc::RpcChannel) -> Option<String> {
+    let capture = traffic::capture_dir(app).and_then(|dir| traffic::Capture::create(&dir, id));
+    match capture {
+        Ok(capture) => {
+            let name = capture.name();
+            channel.set_capture(Some(capture));
+            append_output(app, id, output::Stream::System, format!("Traffic: capturing to {}", name));
+            Some(name)
+        }
+        Err(e) => {
+            append_output(app, id, output::Stream::System, format!("Traffic: {}", e));
+            None
+        }
+    }
+}
+
+// Drops the connection to a server that has no process to signal (a remote one)
+fn close_channel<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    // Unregistered first, so the connection's reader drops anything still in flight
+    let channel = match app.state::<ChannelStore>().inner.lock() {
+        Ok(mut channels) => channels.remove(id),
+        Err(_) => None,
+    };
+    if let Some(channel) = channel {
+        channel.close();
+    }
+}
+
+// Spawns the process for `id` along with its output and monitor threads.
+// `restart_count` is the number of automatic restarts leading up to this spawn (0 for a manual start).
+fn spawn_server<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    server_config: MCPServerConfig,
+    restart_count: u32,
+) -> Result<CommandInfo, String> {
+    let id = id.to_string();
+    let process_store = app.state::<ProcessStore>();
+    let output_store = app.state::<OutputStore>();
+    if process_store.quitting.load(Ordering::SeqCst) {
+        return Err(format!("Not starting '{}': the app is quitting", id));
+    }
+
     // --- Prepare and Spawn --- 
     {
         let mut output_map = output_store.inner.lock().map_err(|e| e.to_string())?;
-        output_map.insert(id.clone(), Vec::new());
+        let new_buffer = || output::OutputBuffer::new(server_config.output_max_lines, server_config.output_max_bytes);
+        if restart_count == 0 {
+            output_map.insert(id.clone(), new_buffer());
+        } else {
+            // Keep the previous run's output around so the crash stays visible
+            output_map.entry(id.clone()).or_insert_with(new_buffer);
+        }
     } // output_store lock released
+    if restart_count == 0 {
+        open_log_file(app, &id, &server_config);
+        sync_bridge(app, &id, &server_config);
+    } else {
+        append_output(app, &id, output::Stream::System, format!("Restarting (attempt {} of {})", restart_count, server_config.max_restarts));
+    }
+
+    let detector = error_detection::ErrorDetector::new(&server_config.error_detection.clone().unwrap_or_default())
+        .unwrap_or_else(|e| {
+            append_output(app, &id, output::Stream::System, format!("{}; using default error detection", e));
+            error_detection::ErrorDetector::new(&Default::default()).expect("default error detection is valid")
+        });
 
     // First, create a CommandInfo with Starting status and insert it
     let starting_info = CommandInfo {
@@ -246,8 +1108,12 @@ async fn start_command(
         status: CommandStatus::Starting,
         is_running: true, 
         has_error: false,
+        error_lines: 0,
+        last_error: None,
         process_id: None,
         port: server_config.port,
+        restart_count,
+        stop_request: None,
     };
     
     // Insert the Starting status immediately so UI can show it
@@ -256,22 +1122,32 @@ async fn start_command(
         // Empty child arc since we haven't spawned yet
         store.insert(id.clone(), (Arc::new(Mutex::new(None)), starting_info.clone()));
     } // process_store lock released
+    events::emit_status(app, &starting_info);
 
-    let (shell, shell_arg) = if cfg!(target_os = "windows") {
-        ("cmd", "/C")
-    } else {
-        ("sh", "-c")
-    };
-    let full_command = format!("{} {}", server_config.command, server_config.args.join(" "));
-
-    let mut command = std::process::Command::new(shell);
-    command
-        .arg(shell_arg)
-        .arg(&full_command)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-    if !server_config.env.is_empty() {
-        command.envs(&server_config.env);
+    let transport = server_config.transport();
+    if transport != remote::Transport::Stdio {
+        return connect_remote(app, &id, &server_config, transport, starting_info);
+    }
+
+    let (mut command, full_command) = build_command(&server_config);
+    command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    apply_environment(&mut command, &server_config);
+    if let Some(cwd) = &server_config.cwd {
+        command.current_dir(cwd);
+    }
+
+    // Give each server its own process group so stop/kill reach everything it spawns,
+    // not just the direct child (or the shell wrapper in shell mode)
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP);
     }
 
     // Try to spawn the process
@@ -281,7 +1157,10 @@ async fn start_command(
         Ok(mut child) => {
             let stdout_opt = child.stdout.take();
             let stderr_opt = child.stderr.take();
+            let channel = child.stdin.take().map(|stdin| Arc::new(rpc::RpcChannel::new(Box::new(stdin))));
+            let process_id = child.id();
             let child_arc = Arc::new(Mutex::new(Some(child)));
+            let started_at = Instant::now();
     
             // Update info to Running now that process is spawned
             let command_info = CommandInfo {
@@ -289,21 +1168,59 @@ async fn start_command(
                 status: CommandStatus::Running,
                 is_running: true, 
                 has_error: false,
-                process_id: Some(child_arc.lock().unwrap().as_ref().unwrap().id()),
+                error_lines: 0,
+                last_error: None,
+                process_id: Some(process_id),
                 port: server_config.port,
+                restart_count,
+                stop_request: None,
             };
+
+            // --- Store Process Info (Lock briefly) ---
+            // Done before the monitor starts so a process that exits immediately can't be
+            // overwritten back to Running after the monitor has recorded its exit
+            let quitting = {
+                let mut store = process_store.inner.lock().map_err(|e| e.to_string())?;
+                let quitting = process_store.quitting.load(Ordering::SeqCst);
+                if !quitting {
+                    store.insert(id.clone(), (Arc::clone(&child_arc), command_info.clone()));
+                    println!("Inserted process {} into store with Running status.", id);
+                }
+                quitting
+            }; // process_store lock released
+            // Reaping already took its list of processes, so this one has to go by itself
+            if quitting {
+                if let Err(e) = kill_process(process_id) {
+                    eprintln!("Quit: Failed to kill {}: {}", id, e);
+                }
+                return Err(format!("Not starting '{}': the app is quitting", id));
+            }
+            events::emit_status(app, &command_info);
+
+            if let Some(channel) = &channel {
+                if server_config.capture_traffic {
+                    start_capture(app, &id, channel);
+                }
+                let channel_store = app.state::<ChannelStore>();
+                let mut channels = channel_store.inner.lock().map_err(|e| e.to_string())?;
+                channels.insert(id.clone(), Arc::clone(channel));
+            }
     
             // --- Spawn Helper Threads --- 
             let process_store_clone = Arc::clone(&process_store.inner);
-            let output_store_clone = Arc::clone(&output_store.inner);
             let child_arc_monitor = Arc::clone(&child_arc);
             let id_clone = id.clone();
+            let app_monitor = app.clone();
+            let channel_monitor = channel.clone();
     
             thread::spawn(move || { // Monitor Thread
                 let mut child_option_guard = child_arc_monitor.lock().expect("Monitor: Failed to lock child arc");
                 if let Some(mut child_instance) = child_option_guard.take() {
                     drop(child_option_guard); // Release lock early
                     let status_result = child_instance.wait();
+                    if let Some(channel) = channel_monitor {
+                        channel.close();
+                    }
                     let (success, exit_code) = match status_result {
                          Ok(status) => {
                              let success = status.success();
@@ -317,15 +1234,44 @@ async fn start_command(
                          },
                          Err(_) => (false, None), // Treat wait error as failure
                     };
+
+                    // A process that ran long enough has earned a fresh set of retries
+                    let previous_restarts = if started_at.elapsed() >= RESTART_RESET_AFTER { 0 } else { restart_count };
+                    let mut scheduled_restart = None;
+                    let mut crash_loop = false;
                     
+                    // The group leader is gone, but whatever it spawned may linger and hold the
+                    // server's port: children that ignored a stop, or the real server behind a
+                    // wrapper like npx that crashed. Reap them before anything restarts.
+                    #[cfg(unix)]
+                    let _ = kill_process(process_id);
+
                     // Update backend state
                     if let Ok(mut store) = process_store_clone.lock() {
                         if let Some((_, info)) = store.get_mut(&id_clone) {
-                            info.is_running = false;
+                            // Exits requested through stop/force kill are final regardless of policy
+                            let stop_request = info.stop_request.take();
                             info.has_error = !success;
-                            info.status = CommandStatus::Finished { code: exit_code, success };
-                            println!("Monitor: Process {} finished. Success: {}. Exit code: {:?}. Updating state.", 
-                                     id_clone, success, exit_code);
+                            info.process_id = None;
+                            if stop_request.is_none() && server_config.restart.should_restart(success) {
+                                if previous_restarts < server_config.max_restarts {
+                                    let attempt = previous_restarts + 1;
+                                    let delay_ms = restart_delay_ms(server_config.restart_backoff_ms, attempt);
+                                    info.status = CommandStatus::Restarting { attempt, delay_ms };
+                                    scheduled_restart = Some((attempt, delay_ms));
+                                } else {
+                                    info.is_running = false;
+                                    crash_loop = true;
+                                    info.status = CommandStatus::CrashLoop { restarts: previous_restarts, code: exit_code };
+                                }
+                            } else {
+                                info.is_running = false;
+                                let ended_by = stop_request.unwrap_or(EndedBy::Exit);
+                                info.status = CommandStatus::Finished { code: exit_code, success, ended_by };
+                            }
+                            println!("Monitor: Process {} finished. Success: {}. Exit code: {:?}. New status: {:?}.", 
+                                     id_clone, success, exit_code, info.status);
+                            events::emit_status(&app_monitor, info);
                         } else {
                             println!("Monitor: Process {} not found in store after finishing.", id_clone);
                         }
@@ -334,16 +1280,30 @@ async fn start_command(
                     }
                     
                     // Log exit status to output
-                    if let Ok(mut output) = output_store_clone.lock() {
-                        if let Some(lines) = output.get_mut(&id_clone) {
-                            let message = match status_result {
-                                Ok(_status) => format!("Process exited with status: {:?} (Success: {})", exit_code, success),
-                                Err(e) => format!("Error waiting for process exit: {}", e),
-                            };
-                            lines.push(message);
+                    let message = match status_result {
+                        Ok(_status) => format!("Process exited with status: {:?} (Success: {})", exit_code, success),
+                        Err(e) => format!("Error waiting for process exit: {}", e),
+                    };
+                    append_output(&app_monitor, &id_clone, output::Stream::System, message);
+                    match scheduled_restart {
+                        Some((attempt, delay_ms)) => append_output(&app_monitor, &id_clone, output::Stream::System, format!(
+                            "Restart policy: restarting in {} ms (attempt {} of {})",
+                            delay_ms, attempt, server_config.max_restarts
+                        )),
+                        None if crash_loop => append_output(&app_monitor, &id_clone, output::Stream::System, format!(
+                            "Restart policy: giving up after {} restarts (crash loop)",
+                            previous_restarts
+                        )),
+                        None => {}
+                    }
+
+                    match scheduled_restart {
+                        Some((attempt, delay_ms)) => {
+                            thread::sleep(Duration::from_millis(delay_ms));
+                            restart_after_backoff(&app_monitor, &id_clone, &child_arc_monitor, attempt);
                         }
-                    } else {
-                         eprintln!("Monitor: Failed to lock output store for {}", id_clone);
+                        // The bridge outlives restarts but not the server
+                        None => bridge::stop(&app_monitor, &id_clone),
                     }
                 } else {
                     drop(child_option_guard);
@@ -353,19 +1313,12 @@ async fn start_command(
     
             if let Some(stdout) = stdout_opt { // Stdout Thread
                 let id_clone_stdout = id.clone();
-                let output_store_stdout = Arc::clone(&output_store.inner);
+                let app_stdout = app.clone();
+                let channel_stdout = channel.clone();
                 thread::spawn(move || {
                     let reader = BufReader::new(stdout);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            if let Ok(mut store) = output_store_stdout.lock() {
-                                if let Some(output) = store.get_mut(&id_clone_stdout) {
-                                    output.push(line);
-                                }
-                            } else {
-                                 eprintln!("Stdout thread: Failed to lock output store for {}", id_clone_stdout);
-                            }
-                        }
+                    for line in reader.lines().map_while(Result::ok) {
+                        handle_server_line(&app_stdout, &id_clone_stdout, channel_stdout.as_ref(), line);
                     }
                     println!("Stdout thread finished for {}", id_clone_stdout);
                 });
@@ -373,46 +1326,34 @@ async fn start_command(
     
             if let Some(stderr) = stderr_opt { // Stderr Thread
                 let id_clone_stderr = id.clone();
-                let output_store_stderr = Arc::clone(&output_store.inner);
+                let app_stderr = app.clone();
                 let process_store_stderr = Arc::clone(&process_store.inner);
                 thread::spawn(move || {
                     let reader = BufReader::new(stderr);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            let mut is_first_error = false;
+                    for line in reader.lines().map_while(Result::ok) {
+                        // Stderr is the normal log channel for stdio servers; only flagged lines count
+                        let is_error = detector.is_error(&line);
+                        if is_error {
                             if let Ok(mut store) = process_store_stderr.lock() {
                                 if let Some((_, info)) = store.get_mut(&id_clone_stderr) {
-                                    if !info.has_error {
-                                        info.has_error = true;
-                                        is_first_error = true;
-                                    }
+                                    info.error_lines += 1;
+                                    info.last_error = Some(line.clone());
+                                    events::emit_status(&app_stderr, info);
                                 }
                             } else {
                                 eprintln!("Stderr thread: Failed to lock process store for {}", id_clone_stderr);
                             }
-                            if is_first_error {
-                                println!("Stderr thread: Set error flag for {}", id_clone_stderr);
-                            }
-    
-                            if let Ok(mut store) = output_store_stderr.lock() {
-                                if let Some(output) = store.get_mut(&id_clone_stderr) {
-                                    output.push(format!("ERROR: {}", line));
-                                }
-                            } else {
-                                eprintln!("Stderr thread: Failed to lock output store for {}", id_clone_stderr);
-                            }
                         }
+
+                        append_line(&app_stderr, &id_clone_stderr, output::Stream::Stderr, line, is_error);
                     }
                     println!("Stderr thread finished for {}", id_clone_stderr);
                 });
             }
     
-            // --- Store Process Info (Lock briefly) ---
-            {
-                let mut store = process_store.inner.lock().map_err(|e| e.to_string())?;
-                store.insert(id.clone(), (child_arc, command_info.clone()));
-                println!("Inserted process {} into store with Running status.", id);
-            } // process_store lock released
+            if let Some(check) = server_config.health_check.clone() {
+                health::spawn_monitor(app.clone(), id.clone(), process_id, server_config.port, check);
+            }
     
             Ok(command_info) // Return the updated info (status: Running)
         },
@@ -426,8 +1367,12 @@ async fn start_command(
                 status: CommandStatus::Error { message: error_message.clone() },
                 is_running: false,
                 has_error: true,
+                error_lines: 0,
+                last_error: None,
                 process_id: None,
                 port: server_config.port,
+                restart_count,
+                stop_request: None,
             };
             
             // Store the error state
@@ -435,6 +1380,8 @@ async fn start_command(
                 let mut store = process_store.inner.lock().map_err(|e| e.to_string())?;
                 store.insert(id.clone(), (Arc::new(Mutex::new(None)), error_info.clone()));
             }
+            events::emit_status(app, &error_info);
+            bridge::stop(app, &id);
             
             // Return the error CommandInfo
             Ok(error_info)
@@ -442,14 +1389,265 @@ async fn start_command(
     }
 }
 
+// Respawns `id` once its backoff has elapsed, unless the entry was stopped or restarted in the meantime
+fn restart_after_backoff<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    previous_child: &Arc<Mutex<Option<Child>>>,
+    attempt: u32,
+) {
+    let process_store = app.state::<ProcessStore>();
+    {
+        let store = match process_store.inner.lock() {
+            Ok(store) => store,
+            Err(e) => {
+                eprintln!("Restart: Failed to lock process store for {}: {}", id, e);
+                return;
+            }
+        };
+        match store.get(id) {
+            Some((child_arc, info))
+                if Arc::ptr_eq(child_arc, previous_child)
+                    && matches!(info.status, CommandStatus::Restarting { .. })
+                    && !process_store.quitting.load(Ordering::SeqCst) => {}
+            _ => {
+                println!("Restart: {} was stopped or restarted during backoff, skipping.", id);
+                return;
+            }
+        }
+    } // process_store lock released
+
+    // Pick up any edits made to the server while it was down
+    let server_config = app
+        .state::<ConfigStore>()
+        .0
+        .lock()
+        .ok()
+        .and_then(|config| config.mcp_servers.get(id).cloned());
+
+    match server_config {
+        Some(server_config) => {
+            if let Err(e) = spawn_server(app, id, server_config, attempt) {
+                eprintln!("Restart: Failed to restart {}: {}", id, e);
+            }
+        }
+        None => {
+            if let Ok(mut store) = process_store.inner.lock() {
+                if let Some((_, info)) = store.get_mut(id) {
+                    info.is_running = false;
+                    info.status = CommandStatus::Error {
+                        message: format!("Server '{}' was removed from the configuration before it could restart", id),
+                    };
+                    events::emit_status(app, info);
+                }
+            }
+        }
+    }
+}
+
+// AttachConsole is per process, so only one terminate_process may borrow a server's console at a time
+#[cfg(windows)]
+static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+
+// Asks a server's whole process group to exit: SIGTERM on Unix, Ctrl-Break on Windows
+fn terminate_process(pid: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        // Servers lead their own process group, so the group id is the leader's pid
+        signal::killpg(Pid::from_raw(pid as i32), Signal::SIGTERM).map_err(|e| e.to_string())
+    }
+
+    #[cfg(windows)]
+    {
+        const CTRL_BREAK_EVENT: u32 = 1;
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn AttachConsole(process_id: u32) -> i32;
+            fn FreeConsole() -> i32;
+            fn GenerateConsoleCtrlEvent(ctrl_event: u32, process_group_id: u32) -> i32;
+        }
+
+        let _guard = CONSOLE_LOCK.lock().map_err(|e| e.to_string())?;
+        // Release builds have no console of their own, so borrow the server's for the call.
+        // Attaching fails harmlessly when the server already shares ours (debug builds).
+        // Servers are started with CREATE_NEW_PROCESS_GROUP, so the group id is the leader's pid
+        // and the event doesn't reach the runner itself.
+        let (sent, error) = unsafe {
+            let attached = AttachConsole(pid) != 0;
+            let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != 0;
+            let error = std::io::Error::last_os_error();
+            if attached {
+                FreeConsole();
+            }
+            (sent, error)
+        };
+        if sent {
+            Ok(())
+        } else {
+            Err(format!("Failed to send Ctrl-Break: {}", error))
+        }
+    }
+}
+
+// Kills a server's process along with everything it spawned
+fn kill_process(pid: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        signal::killpg(Pid::from_raw(pid as i32), Signal::SIGKILL).map_err(|e| e.to_string())
+    }
+
+    #[cfg(windows)]
+    {
+        let status = std::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .status()
+            .map_err(|e| e.to_string())?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("taskkill exited with {}", status))
+        }
+    }
+}
+
+// Stops every server still in the store when the app quits: a graceful stop first,
+// then anything that hasn't exited after QUIT_GRACE_PERIOD is killed
+fn reap_all_processes<R: Runtime>(app: &AppHandle<R>) {
+    let process_store = app.state::<ProcessStore>();
+    let targets: Vec<(String, u32)> = match process_store.inner.lock() {
+        Ok(mut store) => {
+            process_store.quitting.store(true, Ordering::SeqCst);
+            store
+                .iter_mut()
+                .filter_map(|(id, (_, info))| {
+                    let Some(pid) = info.process_id else {
+                        // Waiting out a restart backoff (or never spawned): cancel it outright
+                        if info.is_running || matches!(info.status, CommandStatus::Restarting { .. }) {
+                            info.is_running = false;
+                            info.stop_request = Some(EndedBy::Stop);
+                            info.status = CommandStatus::Finished { code: None, success: false, ended_by: EndedBy::Stop };
+                            events::emit_status(app, info);
+                        }
+                        return None;
+                    };
+                    info.status = CommandStatus::Stopping;
+                    info.stop_request = Some(EndedBy::Stop);
+                    events::emit_status(app, info);
+                    Some((id.clone(), pid))
+                })
+                .collect()
+        }
+        Err(e) => {
+            eprintln!("Quit: Failed to lock process store: {}", e);
+            return;
+        }
+    };
+    if targets.is_empty() {
+        return;
+    }
+
+    println!("Quit: Stopping {} running server(s).", targets.len());
+    for (id, pid) in &targets {
+        if let Err(e) = terminate_process(*pid) {
+            eprintln!("Quit: Failed to send SIGTERM to {}: {}", id, e);
+        }
+    }
+
+    // The monitor threads clear process_id as each process exits
+    let deadline = Instant::now() + QUIT_GRACE_PERIOD;
+    let mut remaining = targets;
+    while !remaining.is_empty() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(100));
+        if let Ok(store) = process_store.inner.lock() {
+            remaining.retain(|(id, pid)| {
+                store.get(id).is_some_and(|(_, info)| info.process_id == Some(*pid))
+            });
+        }
+    }
+
+    for (id, pid) in remaining {
+        println!("Quit: {} did not exit in time, killing its process group.", id);
+        if let Err(e) = kill_process(pid) {
+            eprintln!("Quit: Failed to kill {}: {}", id, e);
+        }
+    }
+}
+
+// Force kills `pid` if it's still the process being stopped for `id` once `timeout` has passed
+fn escalate_stop_after<R: Runtime>(app: AppHandle<R>, id: String, pid: u32, timeout: Duration) {
+    thread::spawn(move || {
+        thread::sleep(timeout);
+
+        {
+            let process_store = app.state::<ProcessStore>();
+            let mut store = match process_store.inner.lock() {
+                Ok(store) => store,
+                Err(e) => {
+                    eprintln!("Stop timeout: Failed to lock process store for {}: {}", id, e);
+                    return;
+                }
+            };
+            let Some((_, info)) = store.get_mut(&id) else {
+                return;
+            };
+            // The monitor clears process_id once the process is gone
+            if info.status != CommandStatus::Stopping || info.process_id != Some(pid) {
+                return;
+            }
+
+            println!("Stop timeout: {} still running after {:?}, escalating to SIGKILL.", id, timeout);
+            info.status = CommandStatus::Killing;
+            info.stop_request = Some(EndedBy::StopTimeout);
+            events::emit_status(&app, info);
+        } // process_store lock released
+
+        // Killing can block (taskkill on Windows), so it runs without the store locked
+        if let Err(e) = kill_process(pid) {
+            eprintln!("Stop timeout: Failed to kill process {}: {}", id, e);
+            let process_store = app.state::<ProcessStore>();
+            let Ok(mut store) = process_store.inner.lock() else {
+                return;
+            };
+            if let Some((_, info)) = store.get_mut(&id) {
+                // Unless the process exited on its own in the meantime
+                if info.status == CommandStatus::Killing && info.process_id == Some(pid) {
+                    info.status = CommandStatus::Error { message: format!("Failed to force kill after stop timeout: {}", e) };
+                    events::emit_status(&app, info);
+                }
+            }
+            return;
+        }
+
+        append_output(&app, &id, output::Stream::System, format!("Process did not exit within {} ms of SIGTERM, sent SIGKILL", timeout.as_millis()));
+    });
+}
+
 #[tauri::command]
-async fn stop_command(
+async fn stop_command<R: Runtime>(
     id: String,
-    process_store: State<'_, ProcessStore>,
+    app: tauri::AppHandle<R>,
 ) -> Result<CommandInfo, String> {
+    stop_server(&app, &id)
+}
+
+// Sends a graceful stop to `id`, arming the stop timeout that escalates to a kill
+fn stop_server<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<CommandInfo, String> {
+    let id = id.to_string();
+    let process_store = app.state::<ProcessStore>();
+    let config_store = app.state::<ConfigStore>();
+
+    let stop_timeout_ms = {
+        let config = config_store.0.lock().map_err(|e| e.to_string())?;
+        config
+            .mcp_servers
+            .get(&id)
+            .map(|server| server.stop_timeout_ms)
+            .unwrap_or_else(default_stop_timeout_ms)
+    }; // config_store lock released
+
     let mut store_guard = process_store.inner.lock().map_err(|e| e.to_string())?;
     
-    if let Some((child_arc, info)) = store_guard.get_mut(&id) {
+    if let Some((_, info)) = store_guard.get_mut(&id) {
         // Check if it's already stopped or stopping
         if !info.is_running && 
            (info.status == CommandStatus::Idle || 
@@ -462,40 +1660,37 @@ async fn stop_command(
         
         // Update status to Stopping first
         info.status = CommandStatus::Stopping;
+        info.stop_request = Some(EndedBy::Stop);
         let updated_info = info.clone();
         
-        let child_option_guard = child_arc.lock().map_err(|e| format!("Stop: Failed to lock child arc for {}: {}", id, e))?;
-        
-        if let Some(child_instance) = child_option_guard.as_ref() { // Borrow without taking
-             let pid = child_instance.id() as i32; // Get process ID
-
-            #[cfg(unix)]
-            {
-                let os_pid = Pid::from_raw(pid);
-                match signal::kill(os_pid, Signal::SIGTERM) {
-                    Ok(_) => println!("Graceful stop: Sent SIGTERM to process {}.", id),
-                    Err(e) => {
-                        eprintln!("Graceful stop: Failed to send SIGTERM to process {}: {}", id, e);
-                        // Update status to Error if signal fails
-                        info.status = CommandStatus::Error { message: format!("Failed to stop: {}", e) };
-                    }
+        // The monitor thread owns the Child while it waits, so signal by PID
+        if let Some(pid) = info.process_id {
+            match terminate_process(pid) {
+                Ok(_) => {
+                    println!("Graceful stop: Sent SIGTERM to process {}.", id);
+                    escalate_stop_after(
+                        app.clone(),
+                        id.clone(),
+                        pid,
+                        Duration::from_millis(stop_timeout_ms),
+                    );
+                }
+                Err(e) => {
+                    eprintln!("Graceful stop: Failed to send SIGTERM to process {}: {}", id, e);
+                    // Update status to Error if signal fails
+                    info.status = CommandStatus::Error { message: format!("Failed to stop: {}", e) };
                 }
-            }
-            
-            #[cfg(windows)]
-            {
-                // On Windows, we can't send signals directly
-                println!("Graceful stop: Windows doesn't support SIGTERM. Waiting for process {} to exit.", id);
-                // The monitor thread will eventually clean up
             }
             
             // We leave the monitor thread to update the final status
         } else {
             println!("Stop: Child process for {} is no longer available.", id);
+            close_channel(app, &id);
             // Update status since there's no process - use exit code 0 for manual stop
-            info.status = CommandStatus::Finished { code: Some(0), success: true };
+            info.status = CommandStatus::Finished { code: Some(0), success: true, ended_by: EndedBy::Stop };
             info.is_running = false;
         }
+        events::emit_status(app, info);
         
         // Return the updated info to client
         Ok(updated_info)
@@ -505,13 +1700,14 @@ async fn stop_command(
 }
 
 #[tauri::command]
-async fn force_kill_command(
+async fn force_kill_command<R: Runtime>(
     id: String,
     process_store: State<'_, ProcessStore>,
+    app: tauri::AppHandle<R>,
 ) -> Result<CommandInfo, String> {
     let mut store_guard = process_store.inner.lock().map_err(|e| e.to_string())?;
     
-    if let Some((child_arc, info)) = store_guard.get_mut(&id) {
+    if let Some((_, info)) = store_guard.get_mut(&id) {
         // Check if already stopped
         if !info.is_running && 
            (info.status == CommandStatus::Idle || 
@@ -524,44 +1720,28 @@ async fn force_kill_command(
         
         // Update status to Killing first
         info.status = CommandStatus::Killing;
+        info.stop_request = Some(EndedBy::ForceKill);
         let updated_info = info.clone();
         
-        let mut child_option_guard = child_arc.lock().map_err(|e| format!("Force kill: Failed to lock child arc for {}: {}", id, e))?;
-        
-        if let Some(child_instance) = child_option_guard.as_mut() {
-            #[cfg(unix)]
-            {
-                let pid = child_instance.id() as i32;
-                let os_pid = Pid::from_raw(pid);
-                match signal::kill(os_pid, Signal::SIGKILL) {
-                    Ok(_) => println!("Force kill: Sent SIGKILL to process {}.", id),
-                    Err(e) => {
-                        eprintln!("Force kill: Failed to send SIGKILL to process {}: {}", id, e);
-                        // Update status to Error if kill fails
-                        info.status = CommandStatus::Error { message: format!("Failed to force kill: {}", e) };
-                    }
-                }
-            }
-            
-            #[cfg(windows)]
-            {
-                match child_instance.kill() {
-                    Ok(_) => println!("Force kill: Killed process {} on Windows.", id),
-                    Err(e) => {
-                        eprintln!("Force kill: Failed to kill process {} on Windows: {}", id, e);
-                        // Update status to Error if kill fails
-                        info.status = CommandStatus::Error { message: format!("Failed to force kill: {}", e) };
-                    }
+        if let Some(pid) = info.process_id {
+            match kill_process(pid) {
+                Ok(_) => println!("Force kill: Sent SIGKILL to process {}.", id),
+                Err(e) => {
+                    eprintln!("Force kill: Failed to send SIGKILL to process {}: {}", id, e);
+                    // Update status to Error if kill fails
+                    info.status = CommandStatus::Error { message: format!("Failed to force kill: {}", e) };
                 }
             }
             
             // Don't wait here, the monitor thread will handle that
         } else {
             println!("Force kill: Child process for {} is no longer available.", id);
+            close_channel(&app, &id);
             // Update status since there's no process - use exit code 1 for force kill
-            info.status = CommandStatus::Finished { code: Some(1), success: false };
+            info.status = CommandStatus::Finished { code: Some(1), success: false, ended_by: EndedBy::ForceKill };
             info.is_running = false;
         }
+        events::emit_status(&app, info);
         
         // Return the updated info with Killing status
         Ok(updated_info)
@@ -570,6 +1750,146 @@ async fn force_kill_command(
     }
 }
 
+// Current status of `id`, or None if it has never been started
+fn current_info<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<Option<CommandInfo>, String> {
+    let store = app.state::<ProcessStore>();
+    let store = store.inner.lock().map_err(|e| e.to_string())?;
+    Ok(store.get(id).map(|(_, info)| info.clone()))
+}
+
+// Blocks until `id` is up and able to serve its dependents, failing if it stops or errors instead.
+// Servers with a health check are only ready once it passes.
+fn wait_until_ready<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    has_health_check: bool,
+    timeout: Duration,
+) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match current_info(app, id)?.map(|info| info.status) {
+            Some(CommandStatus::Healthy) => return Ok(()),
+            Some(CommandStatus::Running) if !has_health_check => return Ok(()),
+            Some(CommandStatus::Running)
+            | Some(CommandStatus::Unhealthy { .. })
+            | Some(CommandStatus::Starting)
+            | Some(CommandStatus::Restarting { .. }) => {}
+            Some(status) => return Err(format!("Dependency '{}' did not start: {:?}", id, status)),
+            None => return Err(format!("Dependency '{}' was never started", id)),
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("Timed out waiting for dependency '{}' to become ready", id));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+// Blocks until `id` is no longer running
+fn wait_until_stopped<R: Runtime>(app: &AppHandle<R>, id: &str, timeout: Duration) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    while current_info(app, id)?.is_some_and(|info| info.is_running) {
+        if Instant::now() >= deadline {
+            return Err(format!("Timed out waiting for '{}' to stop", id));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+// Starts `ids` and everything they depend on, dependencies first. Each server that
+// something later in the order depends on must be ready before the next one starts.
+// A server that fails only holds back its own dependents; the rest still start, and
+// every failure is returned alongside the servers that did start.
+fn start_servers_in_order<R: Runtime>(app: &AppHandle<R>, ids: &[String]) -> Result<(Vec<CommandInfo>, Vec<String>), String> {
+    let config = app.state::<ConfigStore>().0.lock().map_err(|e| e.to_string())?.clone();
+    let order = dependencies::start_order(&config, ids)?;
+
+    let mut infos = Vec::new();
+    let mut errors = Vec::new();
+    let mut failed: HashSet<&str> = HashSet::new();
+    for (index, id) in order.iter().enumerate() {
+        let server = &config.mcp_servers[id];
+        if let Some(dependency) = server.depends_on.iter().find(|dependency| failed.contains(dependency.as_str())) {
+            errors.push(format!("Not starting '{}': dependency '{}' failed", id, dependency));
+            failed.insert(id);
+            continue;
+        }
+
+        let info = match current_info(app, id)? {
+            Some(info) if info.is_running => info,
+            _ => match spawn_server(app, id, server.clone(), 0) {
+                Ok(info) => info,
+                Err(e) => {
+                    errors.push(format!("Failed to start '{}': {}", id, e));
+                    failed.insert(id);
+                    continue;
+                }
+            },
+        };
+        infos.push(info);
+
+        let needed_later = order[index + 1..]
+            .iter()
+            .any(|later| config.mcp_servers[later].depends_on.contains(id));
+        if needed_later {
+            // Health checks only run against local processes
+            let has_health_check = server.health_check.is_some() && server.transport() == remote::Transport::Stdio;
+            if let Err(e) = wait_until_ready(app, id, has_health_check, DEPENDENCY_READY_TIMEOUT) {
+                errors.push(e);
+                failed.insert(id);
+            }
+        }
+    }
+    Ok((infos, errors))
+}
+
+// Stops `ids` and every running server that depends on them, dependents first, waiting for
+// each server to exit before stopping what it depends on. Dependencies of the group are left
+// running, since servers outside it may still use them.
+fn stop_servers_in_order<R: Runtime>(app: &AppHandle<R>, ids: &[String]) -> Result<Vec<CommandInfo>, String> {
+    let config = app.state::<ConfigStore>().0.lock().map_err(|e| e.to_string())?.clone();
+    let order = dependencies::stop_order(&config, ids)?;
+
+    let mut infos = Vec::new();
+    for id in &order {
+        match current_info(app, id)? {
+            Some(info) if info.is_running => {
+                infos.push(stop_server(app, id)?);
+                // Leave room for the stop timeout to escalate before giving up
+                let timeout = Duration::from_millis(config.mcp_servers[id].stop_timeout_ms) + QUIT_GRACE_PERIOD;
+                wait_until_stopped(app, id, timeout)?;
+            }
+            Some(info) => infos.push(info),
+            None => {}
+        }
+    }
+    Ok(infos)
+}
+
+#[tauri::command]
+async fn start_group<R: Runtime>(
+    ids: Vec<String>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<CommandInfo>, String> {
+    let (infos, errors) = tauri::async_runtime::spawn_blocking(move || start_servers_in_order(&app, &ids))
+        .await
+        .map_err(|e| e.to_string())??;
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok(infos)
+}
+
+#[tauri::command]
+async fn stop_group<R: Runtime>(
+    ids: Vec<String>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<CommandInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || stop_servers_in_order(&app, &ids))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 async fn get_command_info(
     id: String,
@@ -585,8 +1905,12 @@ async fn get_command_info(
             status: CommandStatus::Idle,
             is_running: false,
             has_error: false,
+            error_lines: 0,
+            last_error: None,
             process_id: None,
             port: None,
+            restart_count: 0,
+            stop_request: None,
         })
     }
 }
@@ -595,48 +1919,296 @@ async fn get_command_info(
 async fn get_command_output(
     id: String,
     output_store: State<'_, OutputStore>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<output::LogRecord>, String> {
     let store = output_store.inner.lock().map_err(|e| e.to_string())?;
     if let Some(output) = store.get(&id) {
-        println!(
-            "Retrieved {} lines of output for command {}",
-            output.len(),
-            id
-        );
-        Ok(output.clone())
+        Ok(output.records())
     } else {
         println!("No output found for command {}", id);
         Ok(Vec::new())
     }
 }
 
+#[tauri::command]
+async fn get_command_output_since(
+    id: String,
+    cursor: u64,
+    output_store: State<'_, OutputStore>,
+) -> Result<Option<output::OutputChunk>, String> {
+    let store = output_store.inner.lock().map_err(|e| e.to_string())?;
+    Ok(store.get(&id).map(|output| output.since(cursor)))
+}
+
+// Runs the MCP initialize handshake with a running stdio server if it hasn't happened yet,
+// and returns the negotiated protocol version and capabilities
+#[tauri::command]
+async fn get_server_capabilities<R: Runtime>(
+    id: String,
+    app: tauri::AppHandle<R>,
+) -> Result<mcp::ServerSession, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        mcp_channel(&app, &id, mcp::REQUEST_TIMEOUT).map(|(_, session)| session)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn list_tools<R: Runtime>(
+    id: String,
+    refresh: Option<bool>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<inventory::Tool>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (channel, session) = mcp_channel(&app, &id, mcp::REQUEST_TIMEOUT)?;
+        inventory::tools(&channel, &session, refresh.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn list_resources<R: Runtime>(
+    id: String,
+    refresh: Option<bool>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<inventory::Resource>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (channel, session) = mcp_channel(&app, &id, mcp::REQUEST_TIMEOUT)?;
+        inventory::resources(&channel, &session, refresh.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn list_resource_templates<R: Runtime>(
+    id: String,
+    refresh: Option<bool>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<inventory::ResourceTemplate>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (channel, session) = mcp_channel(&app, &id, mcp::REQUEST_TIMEOUT)?;
+        inventory::resource_templates(&channel, &session, refresh.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn list_prompts<R: Runtime>(
+    id: String,
+    refresh: Option<bool>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<inventory::Prompt>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (channel, session) = mcp_channel(&app, &id, mcp::REQUEST_TIMEOUT)?;
+        inventory::prompts(&channel, &session, refresh.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn call_tool<R: Runtime>(
+    server_id: String,
+    tool_name: String,
+    arguments: Option<serde_json::Value>,
+    app: tauri::AppHandle<R>,
+) -> Result<tool_call::ToolCallResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (channel, session) = mcp_channel(&app, &server_id, mcp::REQUEST_TIMEOUT)?;
+        let arguments = arguments.unwrap_or_else(|| serde_json::json!({}));
+        let result = tool_call::call(&channel, &session, &tool_name, arguments);
+        let message = match &result {
+            Ok(result) if result.is_error => format!("MCP: tool '{}' reported an error after {} ms", tool_name, result.duration_ms),
+            Ok(result) => format!("MCP: called tool '{}' in {} ms", tool_name, result.duration_ms),
+            Err(e) => format!("MCP: call to tool '{}' failed: {}", tool_name, e),
+        };
+        append_output(&app, &server_id, output::Stream::System, message);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Starts the gateway on `port`, or the configured/default port
+#[tauri::command]
+async fn start_gateway<R: Runtime>(
+    port: Option<u16>,
+    config_store: State<'_, ConfigStore>,
+    app: tauri::AppHandle<R>,
+) -> Result<gateway::GatewayStatus, String> {
+    let port = match port {
+        Some(port) => port,
+        None => {
+            let config = config_store.0.lock().map_err(|e| e.to_string())?;
+            config.gateway.as_ref().map_or_else(gateway::default_port, |gateway_config| gateway_config.port)
+        }
+    };
+    gateway::start(&app, port)
+}
+
+#[tauri::command]
+async fn stop_gateway<R: Runtime>(app: tauri::AppHandle<R>) -> Result<gateway::GatewayStatus, String> {
+    gateway::stop(&app)?;
+    gateway::status(&app)
+}
+
+#[tauri::command]
+async fn list_bridges<R: Runtime>(app: tauri::AppHandle<R>) -> Result<Vec<bridge::BridgeStatus>, String> {
+    bridge::list(&app)
+}
+
+#[tauri::command]
+async fn get_gateway_status<R: Runtime>(app: tauri::AppHandle<R>) -> Result<gateway::GatewayStatus, String> {
+    gateway::status(&app)
+}
+
+fn running_channel<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<Arc<rpc::RpcChannel>, String> {
+    app.state::<ChannelStore>()
+        .inner
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(id)
+        .cloned()
+        .ok_or_else(|| format!("Server '{}' is not running", id))
+}
+
+// Starts capturing a running server's traffic to a new file and returns its name
+#[tauri::command]
+async fn start_traffic_capture<R: Runtime>(id: String, app: tauri::AppHandle<R>) -> Result<String, String> {
+    let channel = running_channel(&app, &id)?;
+    if let Some(name) = channel.capture_name() {
+        return Ok(name);
+    }
+    start_capture(&app, &id, &channel).ok_or_else(|| format!("Failed to start capturing traffic for '{}'", id))
+}
+
+// Stops capturing and returns the name of the finished capture, if there was one
+#[tauri::command]
+async fn stop_traffic_capture<R: Runtime>(id: String, app: tauri::AppHandle<R>) -> Result<Option<String>, String> {
+    let capture = running_channel(&app, &id)?.set_capture(None);
+    let name = capture.as_ref().map(traffic::Capture::name);
+    if let Some(name) = &name {
+        append_output(&app, &id, output::Stream::System, format!("Traffic: capture {} stopped", name));
+    }
+    Ok(name)
+}
+
+#[tauri::command]
+async fn list_traffic_captures<R: Runtime>(
+    id: Option<String>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<logs::LogFileInfo>, String> {
+    traffic::list(&traffic::capture_dir(&app)?, id.as_deref())
+}
+
+// Re-sends the requests in capture `name` to running server `id` and diffs the responses
+#[tauri::command]
+async fn replay_traffic_capture<R: Runtime>(
+    id: String,
+    name: String,
+    app: tauri::AppHandle<R>,
+) -> Result<traffic::ReplayReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (channel, _) = mcp_channel(&app, &id, mcp::REQUEST_TIMEOUT)?;
+        append_output(&app, &id, output::Stream::System, format!("Traffic: replaying {}", name));
+        traffic::replay(&channel, &traffic::capture_dir(&app)?, &name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// The stdio channel to a running server, initialized for MCP (logging the handshake the first time)
+fn mcp_channel<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    timeout: Duration,
+) -> Result<(Arc<rpc::RpcChannel>, mcp::ServerSession), String> {
+    let channel = running_channel(app, id)?;
+
+    let (session, fresh) = mcp::ensure_initialized(&channel, timeout)?;
+    if fresh {
+        append_output(app, id, output::Stream::System, session.describe());
+    }
+    Ok((channel, session))
+}
+
+#[tauri::command]
+async fn list_log_files<R: Runtime>(
+    id: Option<String>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<logs::LogFileInfo>, String> {
+    logs::list(&logs::log_dir(&app)?, id.as_deref())
+}
+
+#[tauri::command]
+async fn read_log_file<R: Runtime>(
+    name: String,
+    tail: Option<usize>,
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<output::LogRecord>, String> {
+    logs::read(&logs::log_dir(&app)?, &name, tail)
+}
+
 #[tauri::command]
 async fn remove_server<R: Runtime>(
     name: String,
     config_store: State<'_, ConfigStore>,
     app: tauri::AppHandle<R>,
 ) -> Result<Config, String> {
-    let mut config = config_store.0.lock().map_err(|e| e.to_string())?;
+    let mut store = config_store.0.lock().map_err(|e| e.to_string())?;
 
+    let mut config = store.clone();
     if config.mcp_servers.remove(&name).is_none() {
         return Err("Server not found".to_string());
     }
+    let mut dependents: Vec<&String> = config
+        .mcp_servers
+        .iter()
+        .filter(|(_, server)| server.depends_on.contains(&name))
+        .map(|(id, _)| id)
+        .collect();
+    if !dependents.is_empty() {
+        dependents.sort();
+        let dependents: Vec<String> = dependents.iter().map(|id| format!("'{}'", id)).collect();
+        let verb = if dependents.len() == 1 { "depends" } else { "depend" };
+        return Err(format!("Cannot remove '{}': {} {} on it", name, dependents.join(", "), verb));
+    }
+    validate_config(&config).map_err(|e| format!("Cannot remove '{}': {}", name, e))?;
 
-    // Save the updated config
-    let app_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|_| "Failed to get app config directory".to_string())?;
-    fs::create_dir_all(&app_dir)
-        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    config_file::write_default(&app, &config)?;
+    *store = config;
+    Ok(store.clone())
+}
 
-    let config_path = app_dir.join("mcp-config.json");
-    let content = serde_json::to_string_pretty(&*config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+// Backups of the default config, newest first
+#[tauri::command]
+async fn list_config_backups<R: Runtime>(app: tauri::AppHandle<R>) -> Result<Vec<config_file::BackupInfo>, String> {
+    config_file::list_backups(&config_file::default_path(&app)?)
+}
 
-    fs::write(config_path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
+// Makes backup `name` the current config; what it replaces is backed up in turn
+#[tauri::command]
+async fn restore_config_backup<R: Runtime>(
+    name: String,
+    config_store: State<'_, ConfigStore>,
+    app: tauri::AppHandle<R>,
+) -> Result<Config, String> {
+    let path = config_file::default_path(&app)?;
+    let config = config_file::read_backup(&path, &name)?;
+    validate_config(&config).map_err(|e| format!("Cannot restore '{}': {}", name, e))?;
 
-    Ok(config.clone())
+    let previous = {
+        let mut store = config_store.0.lock().map_err(|e| e.to_string())?;
+        config_file::write(&path, &config)?;
+        std::mem::replace(&mut *store, config.clone())
+    };
+    println!("Config: Restored backup {}", name);
+    // The watcher sees the store already matching the file, so announce the change here
+    config_watch::announce(&app, &path, &previous, &config);
+    Ok(config)
 }
 
 #[tauri::command]
@@ -708,16 +2280,49 @@ pub fn run() {
         .manage(ProcessStore::new())
         .manage(ConfigStore(Mutex::new(Config::default())))
         .manage(OutputStore::new())
+        .manage(ChannelStore::new())
+        .manage(gateway::GatewayStore::new())
+        .manage(bridge::BridgeStore::new())
+        .manage(LogStore::new())
+        .manage(config_watch::ConfigWatchStore::new())
         .invoke_handler(tauri::generate_handler![
             start_command,
             stop_command,
             force_kill_command,
+            start_group,
+            stop_group,
             load_config,
             save_config,
             add_server,
             get_command_info,
             get_command_output,
+            get_command_output_since,
+            get_server_capabilities,
+            list_tools,
+            list_resources,
+            list_resource_templates,
+            list_prompts,
+            call_tool,
+            start_gateway,
+            stop_gateway,
+            get_gateway_status,
+            list_bridges,
+            preview_import,
+            import_servers,
+            sync_to_client,
+            list_config_backups,
+            get_config,
+            validate_config_text,
+            get_config_schema,
+            restore_config_backup,
+            start_traffic_capture,
+            stop_traffic_capture,
+            list_traffic_captures,
+            replay_traffic_capture,
+            list_log_files,
+            read_log_file,
             remove_server,
+            update_server,
             fetch_smithery_servers,
             fetch_smithery_server_details,
         ])
@@ -746,8 +2351,20 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            // Start autoStart servers off the main thread so the window isn't held up
+            let handle = app.handle().clone();
+            thread::spawn(move || autostart_servers(&handle));
+            config_watch::spawn(app.handle().clone());
+
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let _ = gateway::stop(app);
+                bridge::stop_all(app);
+                reap_all_processes(app);
+            }
+        });
 }
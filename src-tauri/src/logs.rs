@@ -0,0 +1,232 @@
+// Per-server log files under the app data dir, rotated by size
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::output::{LogRecord, Stream};
+
+pub(crate) struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    // Rotated files kept alongside the live one (<id>.log.1 is the newest)
+    retain: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct LogFileInfo {
+    server_id: String,
+    name: String,
+    size_bytes: u64,
+    modified_ms: Option<u64>,
+}
+
+pub(crate) fn log_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("logs"))
+        .map_err(|_| "Failed to get app data directory".to_string())
+}
+
+// Server ids are user-chosen, so keep only characters that are safe in a file name. Ids that
+// needed changing, or that differ from another only in case (which some file systems ignore),
+// get a hash of the original appended so no two servers share a file.
+pub(crate) fn file_stem(id: &str) -> String {
+    let safe: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    if id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_' || c == '.') {
+        return safe;
+    }
+    // '+' never appears in an id kept as it is, so these can't collide with one
+    format!("{}+{:08x}", safe, fnv1a(id.as_bytes()))
+}
+
+// FNV-1a, for a hash that stays the same across runs and Rust versions
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0x811c_9dc5, |hash, byte| (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193))
+}
+
+impl LogFile {
+    pub(crate) fn open(dir: &Path, id: &str, max_bytes: u64, retain: usize) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+        let path = dir.join(format!("{}.log", file_stem(id)));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open log file {}: {}", path.display(), e))?;
+        let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        Ok(LogFile { path, file, size, max_bytes, retain })
+    }
+
+    // Appends a record as one JSON line, rotating first if it would push the file past its cap
+    pub(crate) fn write(&mut self, record: &LogRecord) -> Result<(), String> {
+        let mut line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        line.push('\n');
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to write log file {}: {}", self.path.display(), e))?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    // <id>.log becomes <id>.log.1, older files shift up by one and the oldest past `retain` is deleted
+    fn rotate(&mut self) -> Result<(), String> {
+        let _ = fs::remove_file(self.rotated_path(self.retain.max(1)));
+        for index in (1..self.retain).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                let _ = fs::rename(&from, self.rotated_path(index + 1));
+            }
+        }
+        if self.retain > 0 {
+            fs::rename(&self.path, self.rotated_path(1))
+                .map_err(|e| format!("Failed to rotate log file {}: {}", self.path.display(), e))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to reopen log file {}: {}", self.path.display(), e))?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+// Log files in `dir`, optionally only those belonging to `id`, newest first
+pub(crate) fn list(dir: &Path, id: Option<&str>) -> Result<Vec<LogFileInfo>, String> {
+    list_files(dir, id, server_id_of)
+}
+
+// Files in `dir` that `server_id_of` maps to a file stem, optionally only `id`'s, newest first
+pub(crate) fn list_files(
+    dir: &Path,
+    id: Option<&str>,
+    server_id_of: fn(&str) -> Option<&str>,
+) -> Result<Vec<LogFileInfo>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let wanted = id.map(file_stem);
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(server_id) = server_id_of(&name) else {
+            continue;
+        };
+        if wanted.as_deref().is_some_and(|wanted| wanted != server_id) {
+            continue;
+        }
+        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        files.push(LogFileInfo {
+            server_id: server_id.to_string(),
+            name,
+            size_bytes: metadata.len(),
+            modified_ms: metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_millis() as u64),
+        });
+    }
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified_ms));
+    Ok(files)
+}
+
+// The file stem a log file name belongs to: "<stem>.log" or "<stem>.log.<n>"
+fn server_id_of(name: &str) -> Option<&str> {
+    if let Some(stem) = name.strip_suffix(".log") {
+        return Some(stem);
+    }
+    let (rest, index) = name.rsplit_once('.')?;
+    if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    rest.strip_suffix(".log")
+}
+
+// Reads the records in a log file listed by `list`, keeping only the last `tail` if given
+pub(crate) fn read(dir: &Path, name: &str, tail: Option<usize>) -> Result<Vec<LogRecord>, String> {
+    let mut records: Vec<LogRecord> = read_lines(dir, name, server_id_of)?
+        .into_iter()
+        .map(|line| {
+            // Keep anything unparseable visible instead of dropping it
+            serde_json::from_str(&line).unwrap_or(LogRecord {
+                seq: 0,
+                timestamp_ms: 0,
+                stream: Stream::System,
+                text: line,
+                error: false,
+            })
+        })
+        .collect();
+    if let Some(tail) = tail {
+        let skip = records.len().saturating_sub(tail);
+        records.drain(..skip);
+    }
+    Ok(records)
+}
+
+// The non-blank lines of file `name` in `dir`, which must be a name `list_files` would return
+pub(crate) fn read_lines(dir: &Path, name: &str, server_id_of: fn(&str) -> Option<&str>) -> Result<Vec<String>, String> {
+    // Only plain names produced by `list_files` are accepted, never paths
+    if server_id_of(name).is_none() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid file name '{}'", name));
+    }
+    let file = File::open(dir.join(name)).map_err(|e| format!("Failed to open {}: {}", name, e))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| !line.trim().is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_stem_keeps_plain_ids() {
+        assert_eq!(file_stem("github"), "github");
+        assert_eq!(file_stem("my-server_2.0"), "my-server_2.0");
+    }
+
+    #[test]
+    fn file_stem_never_maps_two_ids_to_one_file() {
+        let ids = ["my server", "my_server", "my/server", "My_server", "MY_SERVER"];
+        let stems: Vec<String> = ids.iter().map(|id| file_stem(id)).collect();
+        for (index, stem) in stems.iter().enumerate() {
+            assert!(!stem.contains(['/', '\\', ' ']), "{} is not a safe file name", stem);
+            for other in &stems[index + 1..] {
+                assert_ne!(stem.to_lowercase(), other.to_lowercase());
+            }
+        }
+    }
+
+    #[test]
+    fn log_file_names_map_back_to_their_stem() {
+        let stem = file_stem("My Server");
+        assert_eq!(server_id_of(&format!("{}.log", stem)), Some(stem.as_str()));
+        assert_eq!(server_id_of(&format!("{}.log.3", stem)), Some(stem.as_str()));
+        assert_eq!(server_id_of(&format!("{}.txt", stem)), None);
+    }
+}
@@ -0,0 +1,110 @@
+// MCP client side of the stdio channel: the initialize handshake and what it negotiated
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::output;
+use crate::rpc::RpcChannel;
+
+// Newest first; the first entry is what we ask for
+pub(crate) const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Implementation {
+    name: String,
+    version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+}
+
+// What a server told us in its initialize response
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ServerSession {
+    protocol_version: String,
+    server_info: Implementation,
+    // Kept as sent; servers may advertise experimental capabilities we don't model
+    capabilities: Value,
+    instructions: Option<String>,
+    initialized_at_ms: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    #[serde(rename = "serverInfo")]
+    server_info: Implementation,
+    #[serde(default)]
+    capabilities: Value,
+    #[serde(default)]
+    instructions: Option<String>,
+}
+
+// Returns the channel's session, running the handshake first if nobody has yet (the bool
+// says whether this call did). The session lock is held throughout so concurrent callers
+// don't initialize twice.
+pub(crate) fn ensure_initialized(channel: &RpcChannel, timeout: Duration) -> Result<(ServerSession, bool), String> {
+    let mut session = channel.session.lock().map_err(|e| e.to_string())?;
+    if let Some(session) = session.as_ref() {
+        return Ok((session.clone(), false));
+    }
+
+    let result = channel.request(
+        "initialize",
+        json!({
+            "protocolVersion": SUPPORTED_PROTOCOL_VERSIONS[0],
+            "capabilities": {},
+            "clientInfo": { "name": "mcp-server-runner", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        timeout,
+    )?;
+    let result: InitializeResult =
+        serde_json::from_value(result).map_err(|e| format!("Invalid initialize response: {}", e))?;
+    if !SUPPORTED_PROTOCOL_VERSIONS.contains(&result.protocol_version.as_str()) {
+        return Err(format!(
+            "Server wants protocol version {}, which the runner doesn't support (supported: {})",
+            result.protocol_version,
+            SUPPORTED_PROTOCOL_VERSIONS.join(", ")
+        ));
+    }
+    channel.notify("notifications/initialized", json!({}))?;
+
+    let initialized_at_ms = output::now_ms();
+    let new_session = ServerSession {
+        protocol_version: result.protocol_version,
+        server_info: result.server_info,
+        capabilities: result.capabilities,
+        instructions: result.instructions,
+        initialized_at_ms,
+    };
+    *session = Some(new_session.clone());
+    Ok((new_session, true))
+}
+
+impl ServerSession {
+    pub(crate) fn supports(&self, capability: &str) -> bool {
+        self.capabilities.get(capability).is_some_and(|value| !value.is_null())
+    }
+
+    // The initialize result to hand a client that reaches this server through the runner
+    pub(crate) fn initialize_result(&self) -> Value {
+        let mut result = json!({
+            "protocolVersion": self.protocol_version,
+            "capabilities": self.capabilities,
+            "serverInfo": self.server_info,
+        });
+        if let Some(instructions) = &self.instructions {
+            result["instructions"] = json!(instructions);
+        }
+        result
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        format!(
+            "MCP: initialized {} {} (protocol {})",
+            self.server_info.name, self.server_info.version, self.protocol_version
+        )
+    }
+}
@@ -0,0 +1,200 @@
+// Bounded per-server output buffer with cursors for incremental reads
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Distinguishes one buffer from the one that replaced it on the next manual start
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(1);
+
+// Wall-clock milliseconds since the Unix epoch, as used for every timestamp the runner records
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Where a line of output came from
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+    // Written by the runner itself: exit codes, restarts, health checks and the like
+    System,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct LogRecord {
+    pub(crate) seq: u64,
+    pub(crate) timestamp_ms: u64,
+    pub(crate) stream: Stream,
+    pub(crate) text: String,
+    // Flagged by the server's error detection rules
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) error: bool,
+}
+
+pub(crate) struct OutputBuffer {
+    records: VecDeque<LogRecord>,
+    bytes: usize,
+    max_lines: usize,
+    max_bytes: usize,
+    // Sequence number of the oldest record still held; every pushed record gets the next one
+    first_seq: u64,
+    session: u64,
+}
+
+// A slice of a buffer returned by get_command_output_since
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct OutputChunk {
+    // Identifies the buffer; when it changes the server was started afresh and the
+    // caller should discard what it has and read again from cursor 0
+    session: u64,
+    records: Vec<LogRecord>,
+    // Pass this back as the next cursor
+    cursor: u64,
+    // Records between the requested cursor and `records` that were evicted before being read
+    dropped: u64,
+}
+
+impl OutputBuffer {
+    pub(crate) fn new(max_lines: usize, max_bytes: usize) -> Self {
+        OutputBuffer {
+            records: VecDeque::new(),
+            bytes: 0,
+            max_lines: max_lines.max(1),
+            max_bytes,
+            first_seq: 0,
+            session: NEXT_SESSION.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn session(&self) -> u64 {
+        self.session
+    }
+
+    // Appends a line and returns its record, evicting the oldest records once either cap is
+    // exceeded. The newest record is always kept, even if it's over the byte cap on its own.
+    pub(crate) fn push(&mut self, stream: Stream, text: String, error: bool) -> LogRecord {
+        let timestamp_ms = now_ms();
+        let record = LogRecord {
+            seq: self.first_seq + self.records.len() as u64,
+            timestamp_ms,
+            stream,
+            text,
+            error,
+        };
+        self.bytes += record.text.len();
+        self.records.push_back(record.clone());
+        while self.records.len() > 1 && (self.records.len() > self.max_lines || self.bytes > self.max_bytes) {
+            if let Some(evicted) = self.records.pop_front() {
+                self.bytes -= evicted.text.len();
+                self.first_seq += 1;
+            }
+        }
+        record
+    }
+
+    pub(crate) fn records(&self) -> Vec<LogRecord> {
+        self.records.iter().cloned().collect()
+    }
+
+    // Everything pushed at or after `cursor` that is still held
+    pub(crate) fn since(&self, cursor: u64) -> OutputChunk {
+        let next_seq = self.first_seq + self.records.len() as u64;
+        let start = cursor.clamp(self.first_seq, next_seq);
+        OutputChunk {
+            session: self.session,
+            records: self.records.iter().skip((start - self.first_seq) as usize).cloned().collect(),
+            cursor: next_seq,
+            dropped: start.saturating_sub(cursor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(records: &[LogRecord]) -> Vec<&str> {
+        records.iter().map(|record| record.text.as_str()).collect()
+    }
+
+    #[test]
+    fn push_numbers_records_in_order() {
+        let mut buffer = OutputBuffer::new(10, 1024);
+        assert_eq!(buffer.push(Stream::Stdout, "a".to_string(), false).seq, 0);
+        assert_eq!(buffer.push(Stream::Stderr, "b".to_string(), true).seq, 1);
+        assert_eq!(texts(&buffer.records()), ["a", "b"]);
+    }
+
+    #[test]
+    fn push_evicts_the_oldest_past_the_line_cap() {
+        let mut buffer = OutputBuffer::new(2, 1024);
+        for text in ["a", "b", "c"] {
+            buffer.push(Stream::Stdout, text.to_string(), false);
+        }
+        assert_eq!(texts(&buffer.records()), ["b", "c"]);
+        assert_eq!(buffer.first_seq, 1);
+    }
+
+    #[test]
+    fn push_evicts_the_oldest_past_the_byte_cap() {
+        let mut buffer = OutputBuffer::new(10, 5);
+        for text in ["abc", "de", "f"] {
+            buffer.push(Stream::Stdout, text.to_string(), false);
+        }
+        assert_eq!(texts(&buffer.records()), ["de", "f"]);
+        assert_eq!(buffer.bytes, 3);
+    }
+
+    #[test]
+    fn push_keeps_a_single_line_over_the_byte_cap() {
+        let mut buffer = OutputBuffer::new(10, 4);
+        buffer.push(Stream::Stdout, "a".to_string(), false);
+        buffer.push(Stream::Stdout, "much too long".to_string(), false);
+        assert_eq!(texts(&buffer.records()), ["much too long"]);
+        assert_eq!(buffer.bytes, "much too long".len());
+    }
+
+    #[test]
+    fn since_returns_records_from_the_cursor() {
+        let mut buffer = OutputBuffer::new(10, 1024);
+        for text in ["a", "b", "c"] {
+            buffer.push(Stream::Stdout, text.to_string(), false);
+        }
+        let chunk = buffer.since(1);
+        assert_eq!(texts(&chunk.records), ["b", "c"]);
+        assert_eq!(chunk.cursor, 3);
+        assert_eq!(chunk.dropped, 0);
+        assert_eq!(chunk.session, buffer.session());
+    }
+
+    #[test]
+    fn since_reports_records_evicted_before_the_cursor_caught_up() {
+        let mut buffer = OutputBuffer::new(2, 1024);
+        for text in ["a", "b", "c", "d"] {
+            buffer.push(Stream::Stdout, text.to_string(), false);
+        }
+        let chunk = buffer.since(0);
+        assert_eq!(texts(&chunk.records), ["c", "d"]);
+        assert_eq!(chunk.cursor, 4);
+        assert_eq!(chunk.dropped, 2);
+    }
+
+    #[test]
+    fn since_past_the_end_returns_nothing() {
+        let mut buffer = OutputBuffer::new(10, 1024);
+        buffer.push(Stream::Stdout, "a".to_string(), false);
+        let chunk = buffer.since(5);
+        assert!(chunk.records.is_empty());
+        assert_eq!(chunk.cursor, 1);
+        assert_eq!(chunk.dropped, 0);
+    }
+
+    #[test]
+    fn new_buffers_get_distinct_sessions() {
+        assert_ne!(OutputBuffer::new(1, 1).session(), OutputBuffer::new(1, 1).session());
+    }
+}
@@ -0,0 +1,331 @@
+// Remote MCP servers reached by URL instead of a local process: the legacy HTTP+SSE transport
+// and Streamable HTTP. Each connection is an Outbound for the server's RpcChannel plus a
+// receiver of incoming messages, which the runner reads like a local server's stdout.
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use serde_json::{json, Value};
+use tauri::async_runtime::JoinHandle;
+
+use crate::rpc::Outbound;
+
+// How long an SSE connection may take to announce where messages should be posted
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SESSION_HEADER: &str = "Mcp-Session-Id";
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Transport {
+    // A local process speaking JSON-RPC over stdin/stdout
+    Stdio,
+    // GET an event stream, POST messages to the endpoint it announces
+    Sse,
+    // POST every message to one URL; replies come back as JSON or an event stream
+    #[serde(alias = "http")]
+    StreamableHttp,
+}
+
+pub(crate) fn connect(
+    transport: Transport,
+    url: &str,
+    headers: &HashMap<String, String>,
+) -> Result<(Box<dyn Outbound>, mpsc::Receiver<String>), String> {
+    let client = client(headers)?;
+    match transport {
+        Transport::Sse => connect_sse(client, url, headers),
+        Transport::StreamableHttp => {
+            let (inbound, receiver) = mpsc::channel();
+            let outbound = StreamableOutbound {
+                client,
+                url: url.to_string(),
+                session_id: Arc::new(Mutex::new(None)),
+                inbound,
+            };
+            Ok((Box::new(outbound), receiver))
+        }
+        Transport::Stdio => Err("Stdio servers are started as processes, not connected to".to_string()),
+    }
+}
+
+fn header_map(headers: &HashMap<String, String>) -> Result<HeaderMap, String> {
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| format!("Invalid header name '{}': {}", name, e))?;
+        let value = HeaderValue::from_str(value).map_err(|e| format!("Invalid value for header '{}': {}", name, e))?;
+        header_map.insert(name, value);
+    }
+    Ok(header_map)
+}
+
+fn client(headers: &HashMap<String, String>) -> Result<Client, String> {
+    // No overall timeout: event streams stay open, and requests are bounded by the channel
+    Client::builder()
+        .default_headers(header_map(headers)?)
+        .timeout(None)
+        .connect_timeout(ENDPOINT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// Assembles server-sent events from the lines of a stream
+#[derive(Default)]
+struct EventParser {
+    event: String,
+    data: String,
+}
+
+impl EventParser {
+    // The (event, data) completed by `line`, if any
+    fn line(&mut self, line: &str) -> Option<(String, String)> {
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            if self.data.is_empty() {
+                return None;
+            }
+            let name = if event.is_empty() { "message".to_string() } else { event };
+            return Some((name, std::mem::take(&mut self.data)));
+        }
+        if let Some(value) = line.strip_prefix("event:") {
+            self.event = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            if !self.data.is_empty() {
+                self.data.push('\n');
+            }
+            self.data.push_str(value.strip_prefix(' ').unwrap_or(value));
+        }
+        None
+    }
+}
+
+// Calls `on_event(event, data)` for each server-sent event until the stream ends or it returns false
+fn read_events(reader: impl BufRead, mut on_event: impl FnMut(&str, String) -> bool) {
+    let mut parser = EventParser::default();
+    for line in reader.lines().map_while(Result::ok) {
+        if let Some((event, data)) = parser.line(&line) {
+            if !on_event(&event, data) {
+                return;
+            }
+        }
+    }
+}
+
+// The response a waiting request gets when its message never made it to the server
+fn failed_response(message: &str, error: &str) -> Option<String> {
+    let message: Value = serde_json::from_str(message).ok()?;
+    message.get("method")?;
+    let id = message.get("id")?;
+    Some(json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32603, "message": error } }).to_string())
+}
+
+// Legacy HTTP+SSE: replies arrive on the event stream; POSTs are only acknowledged
+struct SseOutbound {
+    client: Client,
+    // Set from the stream's `endpoint` event
+    endpoint: Arc<(Mutex<Option<String>>, Condvar)>,
+    // The task reading the event stream; aborting it drops the response and closes the connection
+    reader: JoinHandle<()>,
+}
+
+// The stream is read on the async runtime rather than a thread, because a blocking read can't be
+// interrupted and the GET would otherwise stay open until the server ends it
+fn connect_sse(
+    client: Client,
+    url: &str,
+    headers: &HashMap<String, String>,
+) -> Result<(Box<dyn Outbound>, mpsc::Receiver<String>), String> {
+    let stream_client = reqwest::Client::builder()
+        .default_headers(header_map(headers)?)
+        .connect_timeout(ENDPOINT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let endpoint = Arc::new((Mutex::new(None), Condvar::new()));
+    let (inbound, receiver) = mpsc::channel();
+    let (connected, connect_result) = mpsc::channel::<Result<(), String>>();
+    let endpoint_reader = Arc::clone(&endpoint);
+    let stream_url = url.to_string();
+    let reader = tauri::async_runtime::spawn(async move {
+        let url = stream_url;
+        let response = match stream_client.get(&url).header(ACCEPT, "text/event-stream").send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                let _ = connected.send(Err(format!("Failed to connect to {}: HTTP {}", url, response.status())));
+                return;
+            }
+            Err(e) => {
+                let _ = connected.send(Err(format!("Failed to connect to {}: {}", url, e)));
+                return;
+            }
+        };
+        let _ = connected.send(Ok(()));
+
+        let base = response.url().clone();
+        let mut response = response;
+        let mut parser = EventParser::default();
+        let mut buffer = Vec::new();
+        while let Ok(Some(chunk)) = response.chunk().await {
+            buffer.extend_from_slice(&chunk);
+            while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some((event, data)) = parser.line(line.trim_end_matches(['\r', '\n'])) else {
+                    continue;
+                };
+                if event == "endpoint" {
+                    let (url, ready) = &*endpoint_reader;
+                    if let (Ok(resolved), Ok(mut url)) = (base.join(data.trim()), url.lock()) {
+                        *url = Some(resolved.to_string());
+                        ready.notify_all();
+                    }
+                } else if inbound.send(data).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    // A server can accept the connection and then never answer; don't leave the start hanging
+    match connect_result.recv_timeout(ENDPOINT_TIMEOUT) {
+        Ok(Ok(())) => Ok((Box::new(SseOutbound { client, endpoint, reader }), receiver)),
+        Ok(Err(e)) => Err(e),
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            reader.abort();
+            Err(format!("Timed out connecting to {} after {:?}", url, ENDPOINT_TIMEOUT))
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err("SSE connection task ended unexpectedly".to_string()),
+    }
+}
+
+impl Drop for SseOutbound {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl Outbound for SseOutbound {
+    fn send(&mut self, message: &str) -> Result<(), String> {
+        let (url, ready) = &*self.endpoint;
+        let url = url.lock().map_err(|e| e.to_string())?;
+        let (url, _) = ready
+            .wait_timeout_while(url, ENDPOINT_TIMEOUT, |url| url.is_none())
+            .map_err(|e| e.to_string())?;
+        let url = url.clone().ok_or_else(|| "Server never announced its message endpoint".to_string())?;
+
+        let response = self
+            .client
+            .post(&url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(message.to_string())
+            .send()
+            .map_err(|e| format!("Failed to post to {}: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to post to {}: HTTP {}", url, response.status()));
+        }
+        Ok(())
+    }
+}
+
+// Streamable HTTP: every message is its own POST, answered in that POST's response
+struct StreamableOutbound {
+    client: Client,
+    url: String,
+    // Handed out by the server on initialize and sent back on every later request
+    session_id: Arc<Mutex<Option<String>>>,
+    inbound: mpsc::Sender<String>,
+}
+
+impl Outbound for StreamableOutbound {
+    // Posts on another thread so a long tool call doesn't hold up everything queued behind it
+    fn send(&mut self, message: &str) -> Result<(), String> {
+        let client = self.client.clone();
+        let url = self.url.clone();
+        let session_id = Arc::clone(&self.session_id);
+        let inbound = self.inbound.clone();
+        let message = message.to_string();
+        thread::spawn(move || post_streamable(&client, &url, &session_id, &inbound, message));
+        Ok(())
+    }
+}
+
+fn post_streamable(
+    client: &Client,
+    url: &str,
+    session_id: &Mutex<Option<String>>,
+    inbound: &mpsc::Sender<String>,
+    message: String,
+) {
+    let fail = |error: String| {
+        if let Some(response) = failed_response(&message, &error) {
+            let _ = inbound.send(response);
+        }
+    };
+
+    let mut request = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(ACCEPT, "application/json, text/event-stream")
+        .body(message.clone());
+    if let Some(session_id) = session_id.lock().ok().and_then(|session_id| session_id.clone()) {
+        request = request.header(SESSION_HEADER, session_id);
+    }
+    let response = match request.send() {
+        Ok(response) => response,
+        Err(e) => return fail(format!("Failed to post to {}: {}", url, e)),
+    };
+
+    // Recorded before the reply is passed on, so whatever is sent next already carries it
+    if let Some(new_session) = response.headers().get(SESSION_HEADER).and_then(|value| value.to_str().ok()) {
+        if let Ok(mut session_id) = session_id.lock() {
+            *session_id = Some(new_session.to_string());
+        }
+    }
+    if !response.status().is_success() {
+        return fail(format!("Server answered HTTP {}", response.status()));
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if content_type.starts_with("text/event-stream") {
+        read_events(BufReader::new(response), |_, data| inbound.send(data).is_ok());
+        return;
+    }
+    // 202 Accepted for notifications comes back empty
+    let Ok(body) = response.text() else {
+        return;
+    };
+    match serde_json::from_str::<Value>(&body) {
+        Ok(Value::Array(batch)) => {
+            for reply in batch {
+                let _ = inbound.send(reply.to_string());
+            }
+        }
+        _ if body.trim().is_empty() => {}
+        _ => {
+            let _ = inbound.send(body.trim().to_string());
+        }
+    }
+}
+
+impl Drop for StreamableOutbound {
+    // Lets the server end the session when the runner disconnects
+    fn drop(&mut self) {
+        let Some(session_id) = self.session_id.lock().ok().and_then(|mut session_id| session_id.take()) else {
+            return;
+        };
+        let client = self.client.clone();
+        let url = self.url.clone();
+        thread::spawn(move || {
+            let _ = client.delete(&url).header(SESSION_HEADER, session_id).send();
+        });
+    }
+}
@@ -0,0 +1,200 @@
+// JSON-RPC 2.0 with a server (its stdin/stdout, or a remote transport), shared by everything
+// in the runner that talks to it
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::ChildStdin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::inventory::Inventory;
+use crate::mcp::ServerSession;
+use crate::traffic::{Capture, Direction};
+
+// What a line from the server's stdout turned out to be
+pub(crate) enum Incoming {
+    // A response to one of our requests, or a server request we've answered
+    Handled,
+    // A JSON-RPC notification
+    Notification { method: String, message: Value },
+    // Anything else: ordinary output
+    Output,
+}
+
+// Where messages to the server go
+pub(crate) trait Outbound: Send {
+    // Sends one serialized JSON-RPC message
+    fn send(&mut self, message: &str) -> Result<(), String>;
+}
+
+impl Outbound for ChildStdin {
+    fn send(&mut self, message: &str) -> Result<(), String> {
+        self.write_all(message.as_bytes())
+            .and_then(|_| self.write_all(b"\n"))
+            .and_then(|_| self.flush())
+            .map_err(|e| format!("Failed to write to server stdin: {}", e))
+    }
+}
+
+pub(crate) struct RpcChannel {
+    outbound: Mutex<Option<Box<dyn Outbound>>>,
+    next_id: AtomicU64,
+    // Requests waiting on a response, keyed by the id we sent
+    pending: Mutex<HashMap<u64, mpsc::Sender<Value>>>,
+    // Set once the MCP initialize handshake has completed on this channel
+    pub(crate) session: Mutex<Option<ServerSession>>,
+    pub(crate) inventory: Mutex<Inventory>,
+    // Every message in either direction is appended here while set
+    capture: Mutex<Option<Capture>>,
+}
+
+impl RpcChannel {
+    pub(crate) fn new(outbound: Box<dyn Outbound>) -> Self {
+        RpcChannel {
+            outbound: Mutex::new(Some(outbound)),
+            next_id: AtomicU64::new(1),
+            pending: Mutex::new(HashMap::new()),
+            session: Mutex::new(None),
+            inventory: Mutex::new(Inventory::default()),
+            capture: Mutex::new(None),
+        }
+    }
+
+    // Starts or stops capturing traffic; returns the capture that was running, if any
+    pub(crate) fn set_capture(&self, capture: Option<Capture>) -> Option<Capture> {
+        let mut current = self.capture.lock().ok()?;
+        std::mem::replace(&mut *current, capture)
+    }
+
+    pub(crate) fn capture_name(&self) -> Option<String> {
+        self.capture.lock().ok()?.as_ref().map(Capture::name)
+    }
+
+    fn record(&self, direction: Direction, message: &Value) {
+        if let Ok(mut capture) = self.capture.lock() {
+            if let Some(capture) = capture.as_mut() {
+                capture.record(direction, message);
+            }
+        }
+    }
+
+    // Sends `method` and blocks until its response arrives, returning the `result`
+    // or the JSON-RPC error message
+    pub(crate) fn request(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let response = self.call(method, params, timeout)?;
+        match response.get("error") {
+            Some(error) => Err(format!(
+                "'{}' failed: {}",
+                method,
+                error.get("message").and_then(Value::as_str).unwrap_or("unknown error")
+            )),
+            None => Ok(response.get("result").cloned().unwrap_or(Value::Null)),
+        }
+    }
+
+    // Like `request`, but hands back the whole response so a JSON-RPC error can be passed on as is
+    pub(crate) fn call(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = mpsc::channel();
+        self.pending.lock().map_err(|e| e.to_string())?.insert(id, sender);
+
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(e) = self.write(&message) {
+            self.forget(id);
+            return Err(e);
+        }
+
+        match receiver.recv_timeout(timeout) {
+            Ok(response) => Ok(response),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                self.forget(id);
+                Err(format!("'{}' timed out after {} ms", method, timeout.as_millis()))
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(format!("Server exited before answering '{}'", method)),
+        }
+    }
+
+    pub(crate) fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        self.write(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    // Hands a stdout line to the request waiting for it, or answers a request the server
+    // sent us. Notifications are passed back to the caller; anything else is output.
+    pub(crate) fn handle_line(&self, line: &str) -> Incoming {
+        if !line.trim_start().starts_with('{') {
+            return Incoming::Output;
+        }
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            return Incoming::Output;
+        };
+        if message.get("jsonrpc").is_some() {
+            self.record(Direction::In, &message);
+        }
+        if let Some(method) = message.get("method").and_then(Value::as_str) {
+            return match message.get("id") {
+                Some(id) => {
+                    self.answer(id, method);
+                    Incoming::Handled
+                }
+                None => Incoming::Notification { method: method.to_string(), message: message.clone() },
+            };
+        }
+        let Some(id) = message.get("id").and_then(Value::as_u64) else {
+            return Incoming::Output;
+        };
+        let sender = match self.pending.lock() {
+            Ok(mut pending) => pending.remove(&id),
+            Err(_) => None,
+        };
+        match sender {
+            Some(sender) => {
+                let _ = sender.send(message);
+                Incoming::Handled
+            }
+            None => Incoming::Output,
+        }
+    }
+
+    // Fails every outstanding request and closes the outbound side; called once the process
+    // has exited or the connection is gone
+    pub(crate) fn close(&self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.clear();
+        }
+        if let Ok(mut outbound) = self.outbound.lock() {
+            outbound.take();
+        }
+    }
+
+    // The runner offers no client capabilities, so the only request it can serve is ping
+    fn answer(&self, id: &Value, method: &str) {
+        let response = if method == "ping" {
+            json!({ "jsonrpc": "2.0", "id": id, "result": {} })
+        } else {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("Method not found: {}", method) },
+            })
+        };
+        if let Err(e) = self.write(&response) {
+            eprintln!("RPC: Failed to answer '{}': {}", method, e);
+        }
+    }
+
+    fn forget(&self, id: u64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+    }
+
+    fn write(&self, message: &Value) -> Result<(), String> {
+        self.record(Direction::Out, message);
+        let mut outbound = self.outbound.lock().map_err(|e| e.to_string())?;
+        let outbound = outbound.as_mut().ok_or_else(|| "Connection to server is closed".to_string())?;
+        outbound.send(&message.to_string())
+    }
+}
@@ -0,0 +1,33 @@
+// JSON Schema validation with errors located by JSON pointer
+use serde_json::Value;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct SchemaError {
+    // JSON pointer into the validated document; empty for the document itself
+    pub(crate) path: String,
+    pub(crate) message: String,
+}
+
+// Every way `instance` fails `schema`, or an error if the schema itself is unusable
+pub(crate) fn validate(schema: &Value, instance: &Value) -> Result<Vec<SchemaError>, String> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| format!("Invalid schema: {}", e))?;
+    Ok(validator
+        .iter_errors(instance)
+        .map(|error| SchemaError {
+            path: error.instance_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect())
+}
+
+// "/path: message; ..." for error strings returned to the frontend
+pub(crate) fn describe(errors: &[SchemaError]) -> String {
+    errors
+        .iter()
+        .map(|error| {
+            let path = if error.path.is_empty() { "/" } else { &error.path };
+            format!("{}: {}", path, error.message)
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -0,0 +1,70 @@
+// Calling a server's tools directly from the runner, for smoke-testing without an editor
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::inventory;
+use crate::mcp::ServerSession;
+use crate::rpc::RpcChannel;
+use crate::schema;
+
+// Tools do real work, so they get longer than the protocol's housekeeping requests
+pub(crate) const TOOL_CALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ToolCallResult {
+    // Content blocks as the server sent them (text, image, audio, resource_link, resource)
+    content: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    structured_content: Option<Value>,
+    // The tool ran but reported failure; protocol errors come back as Err instead
+    pub(crate) is_error: bool,
+    pub(crate) duration_ms: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct CallToolResponse {
+    #[serde(default)]
+    content: Vec<Value>,
+    #[serde(rename = "structuredContent", default)]
+    structured_content: Option<Value>,
+    #[serde(rename = "isError", default)]
+    is_error: bool,
+}
+
+// Checks `arguments` against the tool's inputSchema, then sends tools/call
+pub(crate) fn call(
+    channel: &RpcChannel,
+    session: &ServerSession,
+    tool_name: &str,
+    arguments: Value,
+) -> Result<ToolCallResult, String> {
+    let tools = inventory::tools(channel, session, false)?;
+    let tool = tools
+        .iter()
+        .find(|tool| tool.name == tool_name)
+        .ok_or_else(|| format!("Server has no tool '{}'", tool_name))?;
+
+    let errors = schema::validate(&tool.input_schema, &arguments)
+        .map_err(|e| format!("Tool '{}' has an unusable inputSchema: {}", tool_name, e))?;
+    if !errors.is_empty() {
+        return Err(format!("Invalid arguments for '{}': {}", tool_name, schema::describe(&errors)));
+    }
+
+    let started_at = Instant::now();
+    let result = channel.request(
+        "tools/call",
+        json!({ "name": tool_name, "arguments": arguments }),
+        TOOL_CALL_TIMEOUT,
+    )?;
+    let duration_ms = started_at.elapsed().as_millis() as u64;
+
+    let response: CallToolResponse =
+        serde_json::from_value(result).map_err(|e| format!("Invalid 'tools/call' result: {}", e))?;
+    Ok(ToolCallResult {
+        content: response.content,
+        structured_content: response.structured_content,
+        is_error: response.is_error,
+        duration_ms,
+    })
+}
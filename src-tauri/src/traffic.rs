@@ -0,0 +1,239 @@
+// Capturing a server's JSON-RPC traffic to JSONL, and replaying a capture against it
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::config_schema;
+use crate::logs::{self, file_stem, LogFileInfo};
+use crate::output::now_ms;
+use crate::rpc::RpcChannel;
+use crate::tool_call::TOOL_CALL_TIMEOUT;
+
+// The handshake is redone by the runner for every channel, so replays leave it out
+const HANDSHAKE_METHODS: &[&str] = &["initialize", "notifications/initialized"];
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Direction {
+    // Runner to server
+    Out,
+    // Server to runner
+    In,
+}
+
+// One line of a capture file
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct TrafficRecord {
+    timestamp_ms: u64,
+    direction: Direction,
+    message: Value,
+}
+
+pub(crate) struct Capture {
+    path: PathBuf,
+    file: File,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct Difference {
+    // JSON pointer into the response
+    path: String,
+    expected: Option<Value>,
+    actual: Option<Value>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ReplayResult {
+    method: String,
+    duration_ms: u64,
+    matched: bool,
+    differences: Vec<Difference>,
+    // Set when the request couldn't be replayed at all
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ReplayReport {
+    requests: usize,
+    matched: usize,
+    notifications: usize,
+    results: Vec<ReplayResult>,
+}
+
+pub(crate) fn capture_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("captures"))
+        .map_err(|_| "Failed to get app data directory".to_string())
+}
+
+impl Capture {
+    // A new capture file for `id`, named after the time it was started
+    pub(crate) fn create(dir: &Path, id: &str) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create capture directory: {}", e))?;
+        let path = dir.join(format!("{}-{}.jsonl", file_stem(id), now_ms()));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to create capture file {}: {}", path.display(), e))?;
+        Ok(Capture { path, file })
+    }
+
+    pub(crate) fn name(&self) -> String {
+        self.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    pub(crate) fn record(&mut self, direction: Direction, message: &Value) {
+        let record = TrafficRecord { timestamp_ms: now_ms(), direction, message: message.clone() };
+        let Ok(mut line) = serde_json::to_string(&record) else {
+            return;
+        };
+        line.push('\n');
+        if let Err(e) = self.file.write_all(line.as_bytes()) {
+            eprintln!("Traffic: Failed to write {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+// The file stem a capture file belongs to: "<stem>-<timestamp>.jsonl"
+fn server_id_of(name: &str) -> Option<&str> {
+    let (stem, timestamp) = name.strip_suffix(".jsonl")?.rsplit_once('-')?;
+    (!timestamp.is_empty() && timestamp.chars().all(|c| c.is_ascii_digit())).then_some(stem)
+}
+
+// Capture files in `dir`, optionally only those belonging to `id`, newest first
+pub(crate) fn list(dir: &Path, id: Option<&str>) -> Result<Vec<LogFileInfo>, String> {
+    logs::list_files(dir, id, server_id_of)
+}
+
+fn read(dir: &Path, name: &str) -> Result<Vec<TrafficRecord>, String> {
+    Ok(logs::read_lines(dir, name, server_id_of)?
+        .iter()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+// Re-sends what the runner sent in a capture and compares each response with the captured one.
+// Requests go out one at a time, in their original order.
+pub(crate) fn replay(channel: &RpcChannel, dir: &Path, name: &str) -> Result<ReplayReport, String> {
+    let records = read(dir, name)?;
+
+    // Captured responses, keyed by the id of the request they answer
+    let responses: HashMap<String, &Value> = records
+        .iter()
+        .filter(|record| record.direction == Direction::In && record.message.get("method").is_none())
+        .filter_map(|record| Some((record.message.get("id")?.to_string(), &record.message)))
+        .collect();
+
+    let mut report = ReplayReport { requests: 0, matched: 0, notifications: 0, results: Vec::new() };
+    for record in records.iter().filter(|record| record.direction == Direction::Out) {
+        let Some(method) = record.message.get("method").and_then(Value::as_str) else {
+            // Our replies to the server's own requests
+            continue;
+        };
+        if HANDSHAKE_METHODS.contains(&method) {
+            continue;
+        }
+        let params = record.message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let Some(id) = record.message.get("id") else {
+            report.notifications += 1;
+            channel.notify(method, params)?;
+            continue;
+        };
+
+        report.requests += 1;
+        let started_at = Instant::now();
+        let replayed = channel.call(method, params, TOOL_CALL_TIMEOUT);
+        let duration_ms = started_at.elapsed().as_millis() as u64;
+
+        let result = match (replayed, responses.get(&id.to_string())) {
+            (Ok(actual), Some(expected)) => {
+                let mut differences = Vec::new();
+                diff(&outcome(expected), &outcome(&actual), String::new(), &mut differences);
+                ReplayResult {
+                    method: method.to_string(),
+                    duration_ms,
+                    matched: differences.is_empty(),
+                    differences,
+                    error: None,
+                }
+            }
+            (Ok(_), None) => ReplayResult {
+                method: method.to_string(),
+                duration_ms,
+                matched: false,
+                differences: Vec::new(),
+                error: Some("No response was captured for this request".to_string()),
+            },
+            (Err(e), _) => ReplayResult {
+                method: method.to_string(),
+                duration_ms,
+                matched: false,
+                differences: Vec::new(),
+                error: Some(e),
+            },
+        };
+        if result.matched {
+            report.matched += 1;
+        }
+        report.results.push(result);
+    }
+    Ok(report)
+}
+
+// The part of a response worth comparing: its result or error, without the id
+fn outcome(response: &Value) -> Value {
+    match response.get("error") {
+        Some(error) => json!({ "error": error }),
+        None => json!({ "result": response.get("result").cloned().unwrap_or(Value::Null) }),
+    }
+}
+
+fn diff(expected: &Value, actual: &Value, path: String, differences: &mut Vec<Difference>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_value) in expected {
+                let child = format!("{}{}", path, config_schema::pointer(&[key]));
+                match actual.get(key) {
+                    Some(actual_value) => diff(expected_value, actual_value, child, differences),
+                    None => differences.push(Difference { path: child, expected: Some(expected_value.clone()), actual: None }),
+                }
+            }
+            for (key, actual_value) in actual {
+                if !expected.contains_key(key) {
+                    differences.push(Difference {
+                        path: format!("{}{}", path, config_schema::pointer(&[key])),
+                        expected: None,
+                        actual: Some(actual_value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for index in 0..expected.len().max(actual.len()) {
+                let child = format!("{}/{}", path, index);
+                match (expected.get(index), actual.get(index)) {
+                    (Some(expected_value), Some(actual_value)) => diff(expected_value, actual_value, child, differences),
+                    (expected_value, actual_value) => differences.push(Difference {
+                        path: child,
+                        expected: expected_value.cloned(),
+                        actual: actual_value.cloned(),
+                    }),
+                }
+            }
+        }
+        _ if expected != actual => differences.push(Difference {
+            path,
+            expected: Some(expected.clone()),
+            actual: Some(actual.clone()),
+        }),
+        _ => {}
+    }
+}
@@ -1,7 +1,8 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { confirm } from '@tauri-apps/plugin-dialog';
-import type { MCPCommand, AddMCPCommand, Config, MCPServerConfig } from "./types/mcp";
+import type { MCPCommand, AddMCPCommand, Config, MCPServerConfig, ConfigChangedEvent, ConfigInvalidEvent } from "./types/mcp";
 import { AddMCPCommand as AddMCPCommandForm } from "./components/AddMCPCommand";
 import { TabbedTerminalContainer } from "./components/TabbedTerminalContainer";
 import { ConfigEditor, ConfigEditorRef } from "./components/ConfigEditor";
@@ -17,9 +18,13 @@ type CommandStatus =
   | { state: 'Idle' }
   | { state: 'Starting' }
   | { state: 'Running' }
+  | { state: 'Healthy' }
+  | { state: 'Unhealthy'; failures: number; message: string }
   | { state: 'Stopping' }
   | { state: 'Killing' }
-  | { state: 'Finished'; code: number | null; success: boolean }
+  | { state: 'Restarting'; attempt: number; delay_ms: number }
+  | { state: 'CrashLoop'; restarts: number; code: number | null }
+  | { state: 'Finished'; code: number | null; success: boolean; ended_by: 'exit' | 'stop' | 'stop_timeout' | 'force_kill' }
   | { state: 'Error'; message: string };
 
 // Update CommandInfo interface (used for the polled state)
@@ -41,6 +46,18 @@ const GRACEFUL_STOP_TIMEOUT = 5000;
 
 type ServerViewMode = 'card' | 'table';
 
+const toCommands = (config: Config): MCPCommand[] =>
+  Object.entries(config.mcpServers).map(([id, server]) => ({
+    id,
+    name: id,
+    command: (server as MCPServerConfig).command ?? '',
+    args: (server as MCPServerConfig).args ?? [],
+    url: (server as MCPServerConfig).url,
+    env: (server as MCPServerConfig).env,
+    port: (server as MCPServerConfig).port,
+    isRunning: false, // Initialize according to imported type
+  }));
+
 function App() {
   const [commands, setCommands] = useState<MCPCommand[]>([]);
   const [commandInfo, setCommandInfo] = useState<Record<string, CommandInfo>>({});
@@ -53,6 +70,9 @@ function App() {
   const [activeView, setActiveView] = useState<ActiveView>('servers');
   const configEditorRef = useRef<ConfigEditorRef>(null);
   const forceKillPromptTimerRef = useRef<Record<string, number>>({});
+  // The latest commandInfo, for the status poll
+  const commandInfoRef = useRef(commandInfo);
+  commandInfoRef.current = commandInfo;
   const [serverViewMode, setServerViewMode] = useState<ServerViewMode>(() => {
     if (typeof window !== 'undefined') {
       return (localStorage.getItem('serverViewMode') as ServerViewMode) || 'card';
@@ -72,9 +92,79 @@ function App() {
     // eslint-disable-next-line react-hooks/exhaustive-deps 
   }, []); // Run only on mount
 
-  // Effect for polling command statuses
+  // Re-reads every configured server's info from the backend, for when the UI's copy may be
+  // stale: after loading a config, or when status events were missed
+  const refetchStatuses = useCallback(async () => {
+    try {
+      const config = await invoke<Config>("get_config");
+      const infos = await Promise.all(
+        Object.keys(config.mcpServers).map(id => invoke<CommandInfo>("get_command_info", { id }))
+      );
+      const next: Record<string, CommandInfo> = {};
+      infos.forEach(info => { next[info.id] = info; });
+      setCommandInfo(next);
+    } catch (err) {
+      console.error("Failed to refetch server statuses:", err);
+    }
+  }, []);
+
+  // Apply status changes pushed by the backend as they happen
+  useEffect(() => {
+    let lastSeq: number | null = null;
+    const unlisten = listen<CommandInfo & { seq: number }>('server-status', ({ payload }) => {
+      const { seq, ...info } = payload;
+      const missed = lastSeq !== null && seq !== lastSeq + 1;
+      lastSeq = seq;
+      setCommandInfo(prev => ({ ...prev, [info.id]: info }));
+      if (missed) {
+        refetchStatuses();
+      }
+    });
+    return () => {
+      unlisten.then(unlistenFn => unlistenFn());
+    };
+  },[refetchStatuses]);
+
+  // Pick up edits made to the config file outside the app, keeping the status of known servers
+  useEffect(() => {
+    const unlisten = listen<ConfigChangedEvent>('config-changed', async () => {
+      try {
+        const config = await invoke<Config>("get_config");
+        setError(null);
+        setCommands(toCommands(config));
+        setCommandInfo(prev => {
+          const next: Record<string, CommandInfo> = {};
+          Object.keys(config.mcpServers).forEach(id => {
+            next[id] = prev[id] ?? { id, status: { state: 'Idle' } };
+          });
+          return next;
+        });
+      } catch (err) {
+        console.error("Failed to refresh reloaded config:", err);
+      }
+    });
+    return () => {
+      unlisten.then(unlistenFn => unlistenFn());
+    };
+  }, []);
+
+  // An external edit that doesn't validate is ignored by the backend; say why
+  useEffect(() => {
+    const unlisten = listen<ConfigInvalidEvent>('config-invalid', ({ payload }) => {
+      const problems = payload.errors.map(issue => issue.path ? `${issue.path}: ${issue.message}` : issue.message);
+      setError(`Ignored the change to ${payload.path}: ${problems.join('; ')}`);
+    });
+    return () => {
+      unlisten.then(unlistenFn => unlistenFn());
+    };
+  }, []);
+
+  // Effect for polling command statuses; events deliver changes as they happen,
+  // so this only reconciles anything missed while the listener wasn't attached
   useEffect(() => {
     const intervalId = setInterval(async () => {
+      // Read through the ref so status events don't keep re-creating this interval
+      const commandInfo = commandInfoRef.current;
       // Poll all commands that are potentially active or were recently active
       // A simple approach is to poll all commands present in the `commandInfo` state keys
       // Or poll all commands defined in `commands` array
@@ -168,7 +258,7 @@ function App() {
         }));
       }
 
-    }, 2000); // Poll every 2 seconds
+    }, 10000); // Poll every 10 seconds
 
     // Cleanup interval on component unmount
     return () => {
@@ -177,7 +267,7 @@ function App() {
        Object.values(forceKillPromptTimerRef.current).forEach(clearTimeout);
     };
 
-  }, [commands, commandInfo]); // Depend on commands and commandInfo
+  }, [commands]); // commandInfo is read through commandInfoRef
 
   // Helper to check if a command is in a state that prevents user actions
   const isActionLocked = (status: CommandStatus | undefined): boolean => {
@@ -189,21 +279,10 @@ function App() {
     try {
       setError(null);
       const config = await invoke<Config>("load_config", { configPath: null });
-      const initialInfo: Record<string, CommandInfo> = {};
-      const loadedCommands: MCPCommand[] = Object.entries(config.mcpServers).map(([id, server]) => {
-        initialInfo[id] = { id, status: { state: 'Idle' } };
-        return {
-          id,
-          name: id,
-          command: (server as MCPServerConfig).command,
-          args: (server as MCPServerConfig).args,
-          env: (server as MCPServerConfig).env,
-          port: (server as MCPServerConfig).port,
-          isRunning: false, // Initialize according to imported type
-        };
-      });
-      setCommands(loadedCommands);
-      setCommandInfo(initialInfo);
+      setCommands(toCommands(config));
+      // Servers may already be running (autostart, or a reload after an edit), so ask
+      // rather than assume they're idle
+      await refetchStatuses();
       Object.values(forceKillPromptTimerRef.current).forEach(clearTimeout);
       forceKillPromptTimerRef.current = {};
     } catch (err) {
@@ -245,16 +324,14 @@ function App() {
 
     try {
       setError(null);
-      // Remove original using the originalId
-      await invoke<Config>("remove_server", { name: editedData.originalId }); 
-      // Add new server using data from the form (editedData)
-      await invoke<Config>("add_server", { 
-          name: editedData.name, 
+      // Updated in place so settings the form doesn't show are kept
+      await invoke<Config>("update_server", {
+          originalName: editedData.originalId,
+          name: editedData.name,
           command: editedData.command,
           args: editedData.args,
           env: editedData.env,
           port: editedData.port,
-          // No need to pass isRunning here
        });
       await loadConfig(); // Reload to get fresh state
       setIsAddCommandFormOpen(false);
@@ -294,9 +371,13 @@ function App() {
         case 'Idle':
         case 'Finished':
         case 'Error':
+        case 'CrashLoop':
           targetCommand = "start_command";
           break;
         case 'Running':
+        case 'Restarting':
+        case 'Healthy':
+        case 'Unhealthy':
           targetCommand = "stop_command";
           break;
         case 'Starting':
@@ -660,7 +741,7 @@ function App() {
                 <div className="stat-item">
                   <h3>Running Servers</h3>
                   <div className="stat-value">
-                    {Object.values(commandInfo).filter(info => info.status.state === 'Running').length}
+                    {Object.values(commandInfo).filter(info => ['Running', 'Healthy', 'Unhealthy'].includes(info.status.state)).length}
                   </div>
                 </div>
               </div>
@@ -670,7 +751,7 @@ function App() {
                   {commands.map((cmd: MCPCommand) => {
                     const currentInfo = commandInfo[cmd.id];
                     const status = currentInfo?.status ?? { state: 'Idle' };
-                    const hasError = status.state === 'Error' || (status.state === 'Finished' && !status.success);
+                    const hasError = status.state === 'Error' || status.state === 'CrashLoop' || status.state === 'Unhealthy' || (status.state === 'Finished' && !status.success);
                     const isLocked = isActionLocked(status);
 
                     let buttonContent: React.ReactNode = null;
@@ -695,10 +776,14 @@ function App() {
                            case 'Idle':
                            case 'Finished':
                            case 'Error':
+                           case 'CrashLoop':
                              buttonContent = <><VscDebugStart className="button-icon" /> Start</>;
                              buttonClassName += " start";
                              break;
                            case 'Running':
+                           case 'Restarting':
+                           case 'Healthy':
+                           case 'Unhealthy':
                              buttonContent = <><VscDebugStop className="button-icon" /> Stop</>;
                              buttonClassName += " stop";
                              break;
@@ -759,8 +844,8 @@ function App() {
                          )}
                          <div className="command-info">
                            <div className="info-row">
-                              <span className="info-label">Command</span>
-                              <span className="info-value">{cmd.command} {cmd.args.join(' ')}</span>
+                              <span className="info-label">{cmd.url ? 'URL' : 'Command'}</span>
+                              <span className="info-value">{cmd.url ?? `${cmd.command} ${cmd.args.join(' ')}`}</span>
                             </div>
                             {cmd.port && (
                               <div className="info-row">
@@ -768,13 +853,13 @@ function App() {
                                 <span className="info-value">{cmd.port}</span>
                               </div>
                             )}
-                            {currentInfo?.process_id && status.state === 'Running' && (
+                            {currentInfo?.process_id && ['Running', 'Healthy', 'Unhealthy'].includes(status.state) && (
                               <div className="info-row">
                                 <span className="info-label">Process ID</span>
                                 <span className="info-value">{currentInfo.process_id}</span>
                               </div>
                             )}
-                            {currentInfo?.port && status.state === 'Running' && (
+                            {currentInfo?.port && ['Running', 'Healthy', 'Unhealthy'].includes(status.state) && (
                               <div className="info-row">
                                 <span className="info-label">Active Port</span>
                                 <span className="info-value">{currentInfo.port}</span>
@@ -807,7 +892,7 @@ function App() {
                                    Force Kill
                                 </button>
                             )}
-                            {(status.state === 'Running' || status.state === 'Starting' || status.state === 'Stopping' || status.state === 'Killing') && ( 
+                            {(status.state === 'Running' || status.state === 'Healthy' || status.state === 'Unhealthy' || status.state === 'Restarting' || status.state === 'Starting' || status.state === 'Stopping' || status.state === 'Killing') && ( 
                               <button 
                                 className="action-button secondary"
                                 onClick={() => openTerminalTab(cmd.id)}
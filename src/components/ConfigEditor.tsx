@@ -1,6 +1,6 @@
 import { useState, useEffect, useRef, useCallback, useImperativeHandle, forwardRef } from 'react';
 import { invoke } from "@tauri-apps/api/core";
-import type { Config } from '../types/mcp';
+import type { Config, ConfigIssue } from '../types/mcp';
 
 // Define the shape of the functions we expose via the ref
 export interface ConfigEditorRef {
@@ -13,10 +13,28 @@ interface ConfigEditorProps {
   onSave: (config: Config) => Promise<void>;
 }
 
+// Offset range of the key (or value, for array items) that a JSON pointer refers to in `text`.
+// A textual search rather than a real parse, which is enough to put the cursor in the right place.
+const locatePointer = (text: string, pointer: string): [number, number] | null => {
+  const segments = pointer.split('/').slice(1).map(segment => segment.replace(/~1/g, '/').replace(/~0/g, '~'));
+  let start = 0;
+  let found: [number, number] | null = null;
+  for (const segment of segments) {
+    if (/^\d+$/.test(segment)) continue;
+    const key = JSON.stringify(segment);
+    const index = text.indexOf(`${key}:`, start);
+    if (index === -1) break;
+    found = [index, index + key.length];
+    start = index + key.length;
+  }
+  return found;
+};
+
 // Use forwardRef to pass the ref through
 export const ConfigEditor = forwardRef<ConfigEditorRef, ConfigEditorProps>(({ isVisible, onSave }, ref) => {
   const [configText, setConfigText] = useState('');
   const [error, setError] = useState<string | null>(null);
+  const [issues, setIssues] = useState<ConfigIssue[]>([]);
   const textAreaRef = useRef<HTMLTextAreaElement>(null);
 
   useEffect(() => {
@@ -30,6 +48,7 @@ export const ConfigEditor = forwardRef<ConfigEditorRef, ConfigEditorProps>(({ is
       const config = await invoke<Config>("load_config", { configPath: null });
       setConfigText(JSON.stringify(config, null, 2));
       setError(null);
+      setIssues([]);
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
     }
@@ -39,6 +58,11 @@ export const ConfigEditor = forwardRef<ConfigEditorRef, ConfigEditorProps>(({ is
   const handleSave = useCallback(async () => {
     console.log("Attempting to save config from editor...");
     try {
+      const found = await invoke<ConfigIssue[]>("validate_config_text", { content: configText });
+      setIssues(found);
+      if (found.length > 0) {
+        throw new Error(`The configuration has ${found.length} problem${found.length === 1 ? '' : 's'}`);
+      }
       const config = JSON.parse(configText);
       setError(null);
       await onSave(config);
@@ -59,6 +83,14 @@ export const ConfigEditor = forwardRef<ConfigEditorRef, ConfigEditorProps>(({ is
     save: handleSave
   }));
 
+  const highlight = (issue: ConfigIssue) => {
+    const textArea = textAreaRef.current;
+    const range = locatePointer(configText, issue.path);
+    if (!textArea || !range) return;
+    textArea.focus();
+    textArea.setSelectionRange(range[0], range[1]);
+  };
+
   if (!isVisible) return null;
 
   return (
@@ -66,6 +98,15 @@ export const ConfigEditor = forwardRef<ConfigEditorRef, ConfigEditorProps>(({ is
       {error && (
         <div className="error-message" style={{ marginBottom: '1rem' }}>
           {error}
+          {issues.length > 0 && (
+            <ul className="config-issues">
+              {issues.map((issue, index) => (
+                <li key={index} onClick={() => highlight(issue)}>
+                  <code>{issue.path || '/'}</code> {issue.message}
+                </li>
+              ))}
+            </ul>
+          )}
         </div>
       )}
 
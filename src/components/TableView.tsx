@@ -78,7 +78,7 @@ export const TableView: React.FC<TableViewProps> = ({
           {commands.map((cmd) => {
             const currentInfo = commandInfo[cmd.id];
             const status = currentInfo?.status ?? { state: 'Idle' };
-            const hasError = status.state === 'Error' || (status.state === 'Finished' && !status.success);
+            const hasError = status.state === 'Error' || status.state === 'CrashLoop' || status.state === 'Unhealthy' || (status.state === 'Finished' && !status.success);
             const isLocked = isActionLocked(status);
             let buttonContent: React.ReactNode = null;
             let isButtonDisabled = false;
@@ -87,9 +87,13 @@ export const TableView: React.FC<TableViewProps> = ({
               case 'Idle':
               case 'Finished':
               case 'Error':
+              case 'CrashLoop':
                 buttonContent = <><VscDebugStart className="button-icon" /> Start</>;
                 break;
               case 'Running':
+              case 'Restarting':
+              case 'Healthy':
+              case 'Unhealthy':
                 buttonContent = <><VscDebugStop className="button-icon" /> Stop</>;
                 break;
               case 'Starting':
@@ -113,7 +117,7 @@ export const TableView: React.FC<TableViewProps> = ({
                   {cmd.name}
                 </td>
                 <td>{status.state}{status.state === 'Finished' && status.code !== undefined ? ` (${status.code})` : ''}</td>
-                <td style={{ maxWidth: 200, overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }}>{cmd.command} {cmd.args.join(' ')}</td>
+                <td style={{ maxWidth: 200, overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }}>{cmd.url ?? `${cmd.command} ${cmd.args.join(' ')}`}</td>
                 <td>{cmd.port ?? ''}</td>
                 <td style={{ position: 'relative' }}>
                   <div className="table-actions-group">
@@ -134,7 +138,7 @@ export const TableView: React.FC<TableViewProps> = ({
                         Force Kill
                       </button>
                     )}
-                    {(status.state === 'Running' || status.state === 'Starting' || status.state === 'Stopping' || status.state === 'Killing') && (
+                    {(status.state === 'Running' || status.state === 'Healthy' || status.state === 'Unhealthy' || status.state === 'Restarting' || status.state === 'Starting' || status.state === 'Stopping' || status.state === 'Killing') && (
                       <button
                         className="action-button secondary"
                         onClick={() => onOpenTerminal(cmd.id)}
@@ -1,21 +1,56 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 interface TerminalProps {
   commandId: string;
   isVisible: boolean;
 }
 
+type Stream = 'stdout' | 'stderr' | 'system';
+
+interface LogRecord {
+  seq: number;
+  timestamp_ms: number;
+  stream: Stream;
+  text: string;
+  error?: boolean;
+}
+
+interface OutputChunk {
+  session: number;
+  records: LogRecord[];
+  cursor: number;
+  dropped: number;
+}
+
+interface OutputEvent extends LogRecord {
+  id: string;
+  session: number;
+}
+
+type StreamFilter = 'all' | Stream;
+
+// Lines kept in the view; older ones scroll away
+const MAX_TERMINAL_LINES = 5000;
+
 interface CommandInfo {
   id: string;
   is_running: boolean;
   has_error: boolean;
+  error_lines: number;
+  last_error?: string;
   process_id?: number;
   port?: number;
 }
 
+interface StatusEvent extends CommandInfo {
+  seq: number;
+}
+
 export function Terminal({ commandId, isVisible }: TerminalProps) {
-  const [output, setOutput] = useState<string[]>([]);
+  const [output, setOutput] = useState<LogRecord[]>([]);
+  const [streamFilter, setStreamFilter] = useState<StreamFilter>('all');
   const [commandInfo, setCommandInfo] = useState<CommandInfo | null>(null);
   const contentRef = useRef<HTMLDivElement>(null);
   const overlayRef = useRef<HTMLDivElement>(null);
@@ -23,46 +58,129 @@ export function Terminal({ commandId, isVisible }: TerminalProps) {
   const startHeightRef = useRef(0);
   const startYRef = useRef(0);
   const shouldAutoScrollRef = useRef(true);
+  const sessionRef = useRef<number | null>(null);
+  const cursorRef = useRef(0);
 
   useEffect(() => {
+    sessionRef.current = null;
+    cursorRef.current = 0;
     if (!isVisible) {
       setOutput([]);
       setCommandInfo(null);
       return;
     }
 
-    // Poll for command info
-    const infoInterval = setInterval(async () => {
+    let disposed = false;
+    let catchingUp = false;
+    let missedWhileCatchingUp = false;
+    let lastStatusSeq: number | null = null;
+
+    const appendRecords = (records: LogRecord[], dropped: number) => {
+      if (records.length === 0 && dropped === 0) return;
+      setOutput(prev => {
+        const droppedNotice: LogRecord = {
+          seq: -1,
+          timestamp_ms: Date.now(),
+          stream: 'system',
+          text: `... ${dropped} lines dropped ...`,
+        };
+        const next = dropped > 0
+          ? [...prev, droppedNotice, ...records]
+          : [...prev, ...records];
+        return next.length > MAX_TERMINAL_LINES ? next.slice(-MAX_TERMINAL_LINES) : next;
+      });
+
+      // Auto-scroll to bottom if enabled
+      if (shouldAutoScrollRef.current && contentRef.current) {
+        contentRef.current.scrollTop = contentRef.current.scrollHeight;
+      }
+    };
+
+    // Reads everything after our cursor; used on open and whenever an event shows we missed lines
+    const catchUp = async () => {
+      if (catchingUp) {
+        missedWhileCatchingUp = true;
+        return;
+      }
+      catchingUp = true;
+      try {
+        do {
+          missedWhileCatchingUp = false;
+          const chunk = await invoke<OutputChunk | null>("get_command_output_since", {
+            id: commandId,
+            cursor: cursorRef.current,
+          });
+          if (!chunk || disposed) return;
+
+          // A new session means the server was started afresh; read its output from the top
+          if (sessionRef.current !== chunk.session) {
+            const wasReading = sessionRef.current !== null;
+            sessionRef.current = chunk.session;
+            setOutput([]);
+            if (wasReading && cursorRef.current !== 0) {
+              cursorRef.current = 0;
+              missedWhileCatchingUp = true;
+              continue;
+            }
+          }
+
+          cursorRef.current = chunk.cursor;
+          appendRecords(chunk.records, chunk.dropped);
+        } while (missedWhileCatchingUp && !disposed);
+      } catch (err) {
+        console.error('Failed to get command output:', err);
+      } finally {
+        catchingUp = false;
+      }
+    };
+
+    const refreshInfo = async () => {
       try {
         const info = await invoke<CommandInfo>("get_command_info", {
           id: commandId,
         });
-        setCommandInfo(info);
+        if (!disposed) setCommandInfo(info);
       } catch (err) {
         console.error('Failed to get command info:', err);
       }
-    }, 1000);
+    };
+
+    const unlistenOutput = listen<OutputEvent>("server-output", ({ payload }) => {
+      if (payload.id !== commandId) return;
+      if (catchingUp) {
+        missedWhileCatchingUp = true;
+      } else if (payload.session === sessionRef.current && payload.seq === cursorRef.current) {
+        cursorRef.current += 1;
+        appendRecords([{
+          seq: payload.seq,
+          timestamp_ms: payload.timestamp_ms,
+          stream: payload.stream,
+          text: payload.text,
+          error: payload.error,
+        }], 0);
+      } else if (payload.session !== sessionRef.current || payload.seq > cursorRef.current) {
+        catchUp();
+      }
+    });
 
-    // Poll for command output
-    const outputInterval = setInterval(async () => {
-      try {
-        const lines = await invoke<string[]>("get_command_output", {
-          id: commandId,
-        });
-        setOutput(lines);
-        
-        // Auto-scroll to bottom if enabled
-        if (shouldAutoScrollRef.current && contentRef.current) {
-          contentRef.current.scrollTop = contentRef.current.scrollHeight;
-        }
-      } catch (err) {
-        console.error('Failed to get command output:', err);
+    const unlistenStatus = listen<StatusEvent>("server-status", ({ payload }) => {
+      // Status seqs are shared by all servers, so track them before filtering by id
+      const missed = lastStatusSeq !== null && payload.seq !== lastStatusSeq + 1;
+      lastStatusSeq = payload.seq;
+      if (missed) {
+        refreshInfo();
+      } else if (payload.id === commandId) {
+        setCommandInfo(payload);
       }
-    }, 100);
+    });
+
+    catchUp();
+    refreshInfo();
 
     return () => {
-      clearInterval(infoInterval);
-      clearInterval(outputInterval);
+      disposed = true;
+      unlistenOutput.then(unlisten => unlisten());
+      unlistenStatus.then(unlisten => unlisten());
     };
   }, [commandId, isVisible]);
 
@@ -128,25 +246,38 @@ export function Terminal({ commandId, isVisible }: TerminalProps) {
             {commandInfo?.port && (
               <span className="terminal-meta">Port: {commandInfo.port}</span>
             )}
+            {commandInfo && commandInfo.error_lines > 0 && (
+              <span className="terminal-meta error" title={commandInfo.last_error}>
+                {commandInfo.error_lines} error{commandInfo.error_lines === 1 ? '' : 's'}
+              </span>
+            )}
           </h3>
+          <select
+            className="terminal-filter"
+            value={streamFilter}
+            onChange={e => setStreamFilter(e.target.value as StreamFilter)}
+            onMouseDown={e => e.stopPropagation()}
+          >
+            <option value="all">All output</option>
+            <option value="stdout">stdout</option>
+            <option value="stderr">stderr</option>
+            <option value="system">Runner</option>
+          </select>
         </div>
         <div 
           className="terminal-content" 
           ref={contentRef}
           onScroll={handleScroll}
         >
-          {output.map((line, i) => (
+          {output
+            .filter(record => streamFilter === 'all' || record.stream === streamFilter)
+            .map((record, i) => (
             <div 
               key={i} 
-              className={`terminal-line ${
-                line.toLowerCase().includes('error') || 
-                line.toLowerCase().includes('failed') || 
-                line.toLowerCase().includes('exception')
-                  ? 'error'
-                  : ''
-              }`}
+              title={new Date(record.timestamp_ms).toLocaleString()}
+              className={`terminal-line stream-${record.stream} ${record.error ? 'error' : ''}`}
             >
-              {line}
+              {record.text}
             </div>
           ))}
           {commandInfo && !commandInfo.is_running && (
@@ -1,12 +1,71 @@
+export type RestartPolicy = 'never' | 'on-failure' | 'always';
+
+export type HealthCheck = (
+  | { type: 'tcp' }
+  | { type: 'http'; url?: string }
+  | { type: 'mcp' }
+) & {
+  intervalMs?: number;
+  timeoutMs?: number;
+  initialDelayMs?: number;
+  failureThreshold?: number;
+  restartWhenUnhealthy?: boolean;
+};
+
+export interface ErrorDetection {
+  parseLogLevel?: boolean;
+  patterns?: string[];
+  ignorePatterns?: string[];
+}
+
+export type Transport = 'stdio' | 'sse' | 'streamable-http';
+
 export interface MCPServerConfig {
+  // Empty for remote servers
   command: string;
   args: string[];
+  type?: Transport;
+  url?: string;
+  headers?: Record<string, string>;
   env?: Record<string, string>;
   port?: number;
+  restart?: RestartPolicy;
+  maxRestarts?: number;
+  restartBackoffMs?: number;
+  autoStart?: boolean;
+  dependsOn?: string[];
+  healthCheck?: HealthCheck;
+  errorDetection?: ErrorDetection;
+  cwd?: string;
+  inheritEnv?: boolean;
+  envAllowlist?: string[];
+  envDenylist?: string[];
+  outputMaxLines?: number;
+  outputMaxBytes?: number;
+  persistLogs?: boolean;
+  logFileMaxBytes?: number;
+  logFileRetain?: number;
+  shell?: boolean;
+  stopTimeoutMs?: number;
+  bridge?: boolean;
+  captureTraffic?: boolean;
+}
+
+export interface GatewayConfig {
+  enabled?: boolean;
+  port?: number;
+}
+
+export interface HotReloadConfig {
+  enabled?: boolean;
+  restartChanged?: boolean;
 }
 
 export interface Config {
+  $schema?: string;
   mcpServers: Record<string, MCPServerConfig>;
+  gateway?: GatewayConfig;
+  hotReload?: HotReloadConfig;
 }
 
 export interface MCPCommand {
@@ -16,6 +75,7 @@ export interface MCPCommand {
   args: string[];
   env?: Record<string, string>;
   port?: number;
+  url?: string;
   isRunning: boolean;
 }
 
@@ -25,4 +85,179 @@ export interface AddMCPCommand {
   args: string[];
   env?: Record<string, string>;
   port?: number;
-} 
\ No newline at end of file
+} 
+// Returned by get_server_capabilities once the MCP handshake has run
+export interface ServerSession {
+  protocol_version: string;
+  server_info: { name: string; version: string; title?: string };
+  capabilities: Record<string, unknown>;
+  instructions?: string;
+  initialized_at_ms: number;
+}
+
+// Results of list_tools, list_resources, list_resource_templates and list_prompts (MCP wire format)
+export interface Tool {
+  name: string;
+  title?: string;
+  description?: string;
+  inputSchema: Record<string, unknown>;
+  outputSchema?: Record<string, unknown>;
+  annotations?: Record<string, unknown>;
+}
+
+export interface Resource {
+  uri: string;
+  name: string;
+  title?: string;
+  description?: string;
+  mimeType?: string;
+  size?: number;
+}
+
+export interface ResourceTemplate {
+  uriTemplate: string;
+  name: string;
+  title?: string;
+  description?: string;
+  mimeType?: string;
+}
+
+export interface Prompt {
+  name: string;
+  title?: string;
+  description?: string;
+  arguments?: { name: string; description?: string; required: boolean }[];
+}
+
+// Payload of the server-inventory-changed event
+export interface InventoryChangedEvent {
+  id: string;
+  kind: 'tools' | 'resources' | 'resource_templates' | 'prompts';
+}
+
+// Returned by call_tool
+export interface ToolCallResult {
+  content: Array<{ type: string; [key: string]: unknown }>;
+  structured_content?: unknown;
+  is_error: boolean;
+  duration_ms: number;
+}
+
+// Returned by start_gateway, stop_gateway and get_gateway_status
+export interface GatewayStatus {
+  running: boolean;
+  port?: number;
+  url?: string;
+}
+
+// Returned by list_bridges for each server exposed over HTTP
+export interface BridgeStatus {
+  id: string;
+  port: number;
+  url: string;
+  sse_url: string;
+  clients: number;
+}
+
+// Returned by list_traffic_captures
+export interface CaptureInfo {
+  server_id: string;
+  name: string;
+  size_bytes: number;
+  modified_ms?: number | null;
+}
+
+export interface ReplayDifference {
+  path: string;
+  expected?: unknown;
+  actual?: unknown;
+}
+
+export interface ReplayResult {
+  method: string;
+  duration_ms: number;
+  matched: boolean;
+  differences: ReplayDifference[];
+  error?: string | null;
+}
+
+// Returned by replay_traffic_capture
+export interface ReplayReport {
+  requests: number;
+  matched: number;
+  notifications: number;
+  results: ReplayResult[];
+}
+
+export type MCPClient = 'claude-desktop' | 'cursor' | 'vscode' | 'windsurf';
+
+export type ImportConflict = 'none' | 'identical' | 'differs';
+
+export type OnConflict = 'skip' | 'overwrite' | 'rename';
+
+export interface ImportCandidate {
+  name: string;
+  config: MCPServerConfig;
+  conflict: ImportConflict;
+  warnings: string[];
+}
+
+// Returned by preview_import for each client config file
+export interface ImportSource {
+  client: MCPClient;
+  path: string;
+  exists: boolean;
+  servers: ImportCandidate[];
+  error?: string | null;
+}
+
+// Returned by import_servers
+export interface ImportOutcome {
+  imported: string[];
+  skipped: string[];
+  config: Config;
+}
+
+export interface EntryChange {
+  name: string;
+  change: 'added' | 'updated' | 'unchanged';
+  before?: unknown;
+  after: unknown;
+}
+
+// Returned by sync_to_client
+export interface SyncReport {
+  client: MCPClient;
+  path: string;
+  backup?: string | null;
+  written: boolean;
+  changes: EntryChange[];
+}
+
+// Returned by list_config_backups, newest first
+export interface ConfigBackup {
+  name: string;
+  size_bytes: number;
+  created_ms: number;
+}
+
+// Payload of the config-changed event, sent after an external edit was loaded
+export interface ConfigChangedEvent {
+  path: string;
+  added: string[];
+  removed: string[];
+  modified: string[];
+  restarted: string[];
+}
+
+// Returned by validate_config_text; `path` is a JSON pointer into the config
+export interface ConfigIssue {
+  path: string;
+  message: string;
+}
+
+// Payload of the config-invalid event, sent when an external edit failed validation and was ignored
+export interface ConfigInvalidEvent {
+  path: string;
+  errors: ConfigIssue[];
+}